@@ -0,0 +1,53 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use matasano::set1;
+use matasano::set1::aes::{Aes, Backend};
+use matasano::set1::block_cipher::{self, BlockCipher};
+use std::fs::read_to_string;
+
+const BACKENDS: [Backend; 3] = [Backend::Reference, Backend::TTable, Backend::ConstantTime];
+
+fn encrypt_block(c: &mut Criterion) {
+    let mut group = c.benchmark_group("aes_encrypt_block");
+    group.throughput(Throughput::Bytes(16));
+    for &backend in BACKENDS.iter() {
+        let aes = Aes::new(b"YELLOW SUBMARINE").unwrap().with_backend(backend);
+        let mut block = [0; 16];
+        group.bench_function(BenchmarkId::from_parameter(format!("{:?}", backend)), |b| {
+            b.iter(|| aes.encrypt_block(&mut block))
+        });
+    }
+    group.finish();
+}
+
+fn decrypt_block(c: &mut Criterion) {
+    let mut group = c.benchmark_group("aes_decrypt_block");
+    group.throughput(Throughput::Bytes(16));
+    for &backend in BACKENDS.iter() {
+        let aes = Aes::new(b"YELLOW SUBMARINE").unwrap().with_backend(backend);
+        let mut block = [0; 16];
+        group.bench_function(BenchmarkId::from_parameter(format!("{:?}", backend)), |b| {
+            b.iter(|| aes.decrypt_block(&mut block))
+        });
+    }
+    group.finish();
+}
+
+// decrypting the whole challenge 7 file, including the key schedule
+fn set1_challenge7(c: &mut Criterion) {
+    let contents_s1c7 = read_to_string("resources/s1c7_no_newlines.txt").unwrap();
+    let ciphertext = set1::base64_decode(&contents_s1c7);
+    let mut group = c.benchmark_group("aes_128_ecb_s1c7");
+    group.throughput(Throughput::Bytes(ciphertext.len() as u64));
+    for &backend in BACKENDS.iter() {
+        group.bench_function(BenchmarkId::from_parameter(format!("{:?}", backend)), |b| {
+            b.iter(|| {
+                let aes = Aes::new(b"YELLOW SUBMARINE").unwrap().with_backend(backend);
+                block_cipher::ecb_decrypt(&aes, &ciphertext)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encrypt_block, decrypt_block, set1_challenge7);
+criterion_main!(benches);
@@ -10,9 +10,23 @@ pub mod set8;
 #[cfg(test)]
 mod tests {
     use crate::set1;
-    use arrayref;
+    use crate::set1::aes::Backend;
+    use crate::set1::block_cipher::BlockCipher;
+    use crate::set2;
+    use crate::set2::pkcs7::PaddingError;
+    use crate::set3;
+    use crate::set3::ctr::CounterLayout;
+    use crate::set3::fixed_nonce_ctr::{Alignment, KeystreamRecovery};
+    use crate::set4;
+    use rand::Rng;
     use std::fs::read_to_string;
 
+    const AES_BACKENDS: [Backend; 3] = [
+        Backend::Reference,
+        Backend::TTable,
+        Backend::ConstantTime,
+    ];
+
     #[test]
     fn byte_hamming_distance() {
         assert_eq!(set1::byte_hamming_distance(0x0, 0x0), 0);
@@ -111,6 +125,23 @@ mod tests {
         assert_eq!("Cooking MC's like a pound of bacon", plaintext.unwrap());
     }
 
+    #[test]
+    fn single_byte_xor_every_key() {
+        // a slice of a larger buffer, no Vec needed
+        let buffer = b"header: Cooking MC's like a pound of bacon";
+        let message = &buffer[8..];
+        assert_eq!(
+            set1::single_byte_xor(&set1::single_byte_xor(message, 0x5a), 0x5a),
+            message
+        );
+
+        // 0xff is a key like any other, the search used to stop at 0xfe
+        let ciphertext = set1::single_byte_xor(message, 0xff);
+        let (plaintext, key, _) = set1::decrypt_single_byte_xor_english(&ciphertext);
+        assert_eq!(key, 0xff);
+        assert_eq!(plaintext.unwrap().as_bytes(), message);
+    }
+
     #[test]
     fn set1_challenge4() {
         let contents = read_to_string("resources/s1c4.txt").unwrap();
@@ -190,11 +221,246 @@ I go crazy when I hear a cymbal";
     #[test]
     fn set1_challenge7() {
         let contents_s1c7 = read_to_string("resources/s1c7_no_newlines.txt").unwrap();
-        let key : &[u8; 16] = arrayref::array_ref!("YELLOW SUBMARINE".as_bytes(), 0, 16);
+        let key: &[u8; 16] = arrayref::array_ref!("YELLOW SUBMARINE".as_bytes(), 0, 16);
         let ciphertext = set1::base64_decode(&contents_s1c7);
         let plaintext = set1::aes::aes_128_ecb(&ciphertext, key);
-        println!("ciphertext: {:?}", ciphertext);
-        println!("plaintext: {:?}", plaintext);
+        let solution_from_file = read_to_string("resources/s1c6_solution.txt").unwrap();
+
+        // the challenge 7 plaintext is the same as challenge 6's, followed by 4 bytes of PKCS#7 padding
+        assert_eq!(&plaintext[..plaintext.len() - 4], solution_from_file.as_bytes());
+        assert_eq!(&plaintext[plaintext.len() - 4..], &[4, 4, 4, 4]);
+    }
+
+    #[test]
+    fn aes_fips_197_appendix_b() {
+        let key = set1::decode_hex("2b7e151628aed2a6abf7158809cf4f3c");
+        let plaintext = set1::decode_hex("3243f6a8885a308d313198a2e0370734");
+        let ciphertext = set1::decode_hex("3925841d02dc09fbdc118597196a0b32");
+        let key = arrayref::array_ref!(key, 0, 16);
+
+        assert_eq!(
+            set1::aes::encrypt_block(arrayref::array_ref!(plaintext, 0, 16), key).to_vec(),
+            ciphertext
+        );
+        assert_eq!(
+            set1::aes::decrypt_block(arrayref::array_ref!(ciphertext, 0, 16), key).to_vec(),
+            plaintext
+        );
+    }
+
+    #[test]
+    fn aes_fips_197_appendix_c() {
+        let plaintext = set1::decode_hex("00112233445566778899aabbccddeeff");
+        let vectors = [
+            (
+                "000102030405060708090a0b0c0d0e0f",
+                "69c4e0d86a7b0430d8cdb78070b4c55a",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f1011121314151617",
+                "dda97ca4864cdfe06eaf70a0ec0d7191",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "8ea2b7ca516745bfeafc49904b496089",
+            ),
+        ];
+        for (key, ciphertext) in vectors.iter() {
+            let aes = set1::aes::Aes::new(&set1::decode_hex(key)).unwrap();
+            let ciphertext = set1::decode_hex(ciphertext);
+
+            for &backend in AES_BACKENDS.iter() {
+                let aes = aes.clone().with_backend(backend);
+                let mut block = plaintext.clone();
+                aes.encrypt_block(&mut block);
+                assert_eq!(block, ciphertext);
+                aes.decrypt_block(&mut block);
+                assert_eq!(block, plaintext);
+            }
+        }
+    }
+
+    #[test]
+    fn aes_aesavs_known_answer_tests() {
+        // (key, plaintext, ciphertext) samples from the AESAVS GFSbox, KeySbox and VarTxt/VarKey tests
+        let vectors = [
+            (
+                "00000000000000000000000000000000",
+                "f34481ec3cc627bacd5dc3fb08f273e6",
+                "0336763e966d92595a567cc9ce537f5e",
+            ),
+            (
+                "10a58869d74be5a374cf867cfb473859",
+                "00000000000000000000000000000000",
+                "6d251e6944b051e04eaa6fb4dbf78465",
+            ),
+            (
+                "00000000000000000000000000000000",
+                "80000000000000000000000000000000",
+                "3ad78e726c1ec02b7ebfe92b23d9ec34",
+            ),
+            (
+                "000000000000000000000000000000000000000000000000",
+                "1b077a6af4b7f98229de786d7516b639",
+                "275cfc0413d8ccb70513c3859b1d0f72",
+            ),
+            (
+                "e9f065d7c13573587f7875357dfbb16c53489f6a4bd0f7cd",
+                "00000000000000000000000000000000",
+                "0956259c9cd5cfd0181cca53380cde06",
+            ),
+            (
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "014730f80ac625fe84f026c60bfd547d",
+                "5c9d844ed46f9885085e5d6a4f94c7d7",
+            ),
+            (
+                "c47b0294dbbbee0fec4757f22ffeee3587ca4730c3d33b691df38bab076bc558",
+                "00000000000000000000000000000000",
+                "46f2fb342d6f0ab477476fc501242c5f",
+            ),
+        ];
+        for (key, plaintext, ciphertext) in vectors.iter() {
+            let aes = set1::aes::Aes::new(&set1::decode_hex(key)).unwrap();
+            let plaintext = set1::decode_hex(plaintext);
+            let ciphertext = set1::decode_hex(ciphertext);
+
+            for &backend in AES_BACKENDS.iter() {
+                let aes = aes.clone().with_backend(backend);
+                let mut block = plaintext.clone();
+                aes.encrypt_block(&mut block);
+                assert_eq!(block, ciphertext);
+                aes.decrypt_block(&mut block);
+                assert_eq!(block, plaintext);
+            }
+        }
+    }
+
+    #[test]
+    fn aes_round_count_and_invalid_key_length() {
+        assert_eq!(set1::aes::Aes::new(&[0; 16]).unwrap().round_count(), 10);
+        assert_eq!(set1::aes::Aes::new(&[0; 24]).unwrap().round_count(), 12);
+        assert_eq!(set1::aes::Aes::new(&[0; 32]).unwrap().round_count(), 14);
+        assert!(set1::aes::Aes::new(&[0; 20]).is_err());
+        assert!(set1::aes::Aes::with_round_count(&[0; 16], 0).is_err());
+    }
+
+    #[test]
+    fn aes_round_counts_past_255() {
+        // x has order 51 in GF(2^8), so Rcon repeats rather than running out
+        let round_constants = set1::aes::round_constants(300);
+        assert_eq!(round_constants[255], round_constants[255 - 51]);
+        assert_eq!(round_constants[51], 0x0100_0000);
+
+        let cases = [(16, 254), (16, 255), (16, 256), (24, 255), (32, 1000)];
+        for &(key_length, round_count) in cases.iter() {
+            let key = vec![7; key_length];
+            let aes = set1::aes::Aes::with_round_count(&key, round_count).unwrap();
+            assert_eq!(aes.round_count(), round_count);
+            let mut expected = [0x42; 16];
+            aes.clone().with_backend(Backend::Reference).encrypt_block(&mut expected);
+            for &backend in AES_BACKENDS.iter() {
+                let aes = aes.clone().with_backend(backend);
+                let mut block = [0x42; 16];
+                aes.encrypt_block(&mut block);
+                assert_eq!(block, expected, "{:?}", backend);
+                aes.decrypt_block(&mut block);
+                assert_eq!(block, [0x42; 16], "{:?}", backend);
+            }
+        }
+    }
+
+    #[test]
+    fn aes_backends_agree_on_random_inputs() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let key: Vec<u8> = (0..[16, 24, 32][rng.gen_range(0..3)])
+                .map(|_| rng.gen())
+                .collect();
+            let round_count = rng.gen_range(1..=14);
+            let block: [u8; 16] = rng.gen();
+            let aes = set1::aes::Aes::with_round_count(&key, round_count).unwrap();
+
+            let reference = aes.clone().with_backend(Backend::Reference);
+            let mut expected_ciphertext = block;
+            reference.encrypt_block(&mut expected_ciphertext);
+            let mut expected_plaintext = block;
+            reference.decrypt_block(&mut expected_plaintext);
+            for &backend in AES_BACKENDS.iter() {
+                let aes = aes.clone().with_backend(backend);
+                let mut ciphertext = block;
+                aes.encrypt_block(&mut ciphertext);
+                assert_eq!(ciphertext, expected_ciphertext, "{:?}", backend);
+                let mut plaintext = block;
+                aes.decrypt_block(&mut plaintext);
+                assert_eq!(plaintext, expected_plaintext, "{:?}", backend);
+            }
+        }
+    }
+
+    #[test]
+    fn aes_backends_agree_on_every_s_box_input() {
+        // a single round sends every plaintext byte through SubBytes exactly once
+        let plaintext: Vec<u8> = (0..=255).collect();
+        let aes = set1::aes::Aes::with_round_count(&[0; 16], 1).unwrap();
+        let expected = set1::block_cipher::ecb_encrypt(
+            &aes.clone().with_backend(Backend::Reference),
+            &plaintext,
+        );
+        for &backend in AES_BACKENDS.iter() {
+            let aes = aes.clone().with_backend(backend);
+            let ciphertext = set1::block_cipher::ecb_encrypt(&aes, &plaintext);
+            assert_eq!(ciphertext, expected, "{:?}", backend);
+            assert_eq!(
+                set1::block_cipher::ecb_decrypt(&aes, &ciphertext),
+                plaintext,
+                "{:?}",
+                backend
+            );
+        }
+    }
+
+    #[test]
+    fn reduced_round_aes() {
+        let key = "YELLOW SUBMARINE".as_bytes();
+        let plaintext = "sixteen byte msg".as_bytes();
+        let full = set1::aes::Aes::new(key).unwrap();
+        let reduced = set1::aes::Aes::with_round_count(key, 4).unwrap();
+
+        let ciphertext = set1::block_cipher::ecb_encrypt(&reduced, plaintext);
+        assert_ne!(ciphertext, set1::block_cipher::ecb_encrypt(&full, plaintext));
+        assert_eq!(set1::block_cipher::ecb_decrypt(&reduced, &ciphertext), plaintext);
+    }
+
+    // a toy cipher, to check that the modes of operation only rely on the BlockCipher trait
+    struct IdentityCipher {
+        block_size: usize,
+    }
+
+    impl BlockCipher for IdentityCipher {
+        fn new(key: &[u8]) -> Result<IdentityCipher, String> {
+            Ok(IdentityCipher {
+                block_size: key.len(),
+            })
+        }
+        fn block_size(&self) -> usize {
+            self.block_size
+        }
+        fn encrypt_block(&self, block: &mut [u8]) {
+            assert_eq!(block.len(), self.block_size);
+        }
+        fn decrypt_block(&self, block: &mut [u8]) {
+            assert_eq!(block.len(), self.block_size);
+        }
+    }
+
+    #[test]
+    fn ecb_with_identity_cipher() {
+        let cipher = IdentityCipher::new(&[0; 8]).unwrap();
+        let plaintext = "three 8 byte blocks.....".as_bytes();
+
+        assert_eq!(set1::block_cipher::ecb_encrypt(&cipher, plaintext), plaintext);
+        assert_eq!(set1::block_cipher::ecb_decrypt(&cipher, plaintext), plaintext);
     }
 
     #[test]
@@ -202,4 +468,1114 @@ I go crazy when I hear a cymbal";
         assert_eq!(set1::aes::round_constants(10), [0x01000000, 0x02000000, 0x04000000, 0x08000000, 0x10000000, 0x20000000, 0x40000000, 0x80000000, 0x1B000000, 0x36000000]);
     }
 
+    #[test]
+    fn set2_challenge9() {
+        assert_eq!(
+            set2::pkcs7::pad(b"YELLOW SUBMARINE", 20),
+            b"YELLOW SUBMARINE\x04\x04\x04\x04".to_vec()
+        );
+        assert_eq!(set2::pkcs7::pad(b"YELLOW SUBMARINE", 16), {
+            let mut padded = b"YELLOW SUBMARINE".to_vec();
+            padded.extend_from_slice(&[16; 16]);
+            padded
+        });
+        assert_eq!(set2::pkcs7::pad(b"", 8), vec![8; 8]);
+    }
+
+    #[test]
+    fn set2_challenge15() {
+        assert_eq!(
+            set2::pkcs7::unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16),
+            Ok(b"ICE ICE BABY".to_vec())
+        );
+        assert_eq!(
+            set2::pkcs7::unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16),
+            Err(PaddingError::InconsistentPadBytes)
+        );
+        assert_eq!(
+            set2::pkcs7::unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16),
+            Err(PaddingError::InconsistentPadBytes)
+        );
+    }
+
+    #[test]
+    fn pkcs7_unpad_errors() {
+        assert_eq!(set2::pkcs7::unpad(b"", 16), Err(PaddingError::BadLength));
+        assert_eq!(
+            set2::pkcs7::unpad(b"ICE ICE BABY\x03\x03\x03", 16),
+            Err(PaddingError::BadLength)
+        );
+        assert_eq!(
+            set2::pkcs7::unpad(b"ICE ICE BABY\x04\x04\x04\x00", 16),
+            Err(PaddingError::ZeroPadByte)
+        );
+        assert_eq!(
+            set2::pkcs7::unpad(b"ICE ICE BABY\x04\x04\x04\x11", 16),
+            Err(PaddingError::BadLength)
+        );
+        assert_eq!(set2::pkcs7::unpad(&[16; 16], 16), Ok(vec![]));
+    }
+
+    #[test]
+    fn set2_challenge10() {
+        let contents_s2c10 = read_to_string("resources/s2c10_no_newlines.txt").unwrap();
+        let ciphertext = set1::base64_decode(&contents_s2c10);
+        let aes = set1::aes::Aes::new(b"YELLOW SUBMARINE").unwrap();
+        let plaintext = set2::cbc::decrypt(&aes, &[0; 16], &ciphertext).unwrap();
+        let solution_from_file = read_to_string("resources/s1c6_solution.txt").unwrap();
+
+        assert_eq!(plaintext, solution_from_file.as_bytes());
+        assert_eq!(set2::cbc::encrypt(&aes, &[0; 16], &plaintext), ciphertext);
+    }
+
+    #[test]
+    fn cbc_nist_sp_800_38a() {
+        let iv = set1::decode_hex("000102030405060708090a0b0c0d0e0f");
+        let plaintext = set1::decode_hex(concat!(
+            "6bc1bee22e409f96e93d7e117393172a",
+            "ae2d8a571e03ac9c9eb76fac45af8e51",
+            "30c81c46a35ce411e5fbc1191a0a52ef",
+            "f69f2445df4f9b17ad2b417be66c3710"
+        ));
+        let vectors = [
+            (
+                "2b7e151628aed2a6abf7158809cf4f3c",
+                concat!(
+                    "7649abac8119b246cee98e9b12e9197d",
+                    "5086cb9b507219ee95db113a917678b2",
+                    "73bed6b8e3c1743b7116e69e22229516",
+                    "3ff1caa1681fac09120eca307586e1a7"
+                ),
+            ),
+            (
+                "8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b",
+                concat!(
+                    "4f021db243bc633d7178183a9fa071e8",
+                    "b4d9ada9ad7dedf4e5e738763f69145a",
+                    "571b242012fb7ae07fa9baac3df102e0",
+                    "08b0e27988598881d920a9e64f5615cd"
+                ),
+            ),
+            (
+                "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4",
+                concat!(
+                    "f58c4c04d6e5f1ba779eabfb5f7bfbd6",
+                    "9cfc4e967edb808d679f777bc6702c7d",
+                    "39f23369a9d9bacfa530e26304231461",
+                    "b2eb05e2c39be9fcda6c19078c6a9d1b"
+                ),
+            ),
+        ];
+        for (key, ciphertext) in vectors.iter() {
+            let aes = set1::aes::Aes::new(&set1::decode_hex(key)).unwrap();
+            let ciphertext = set1::decode_hex(ciphertext);
+
+            assert_eq!(set2::cbc::encrypt_blocks(&aes, &iv, &plaintext), ciphertext);
+            assert_eq!(set2::cbc::decrypt_blocks(&aes, &iv, &ciphertext), plaintext);
+        }
+    }
+
+    #[test]
+    fn cbc_streaming_in_random_chunks() {
+        let mut rng = rand::thread_rng();
+        let aes = set1::aes::Aes::new(b"YELLOW SUBMARINE").unwrap();
+        let iv: [u8; 16] = rng.gen();
+        for length in 0..100 {
+            let plaintext: Vec<u8> = (0..length).map(|_| rng.gen()).collect();
+            let expected_ciphertext = set2::cbc::encrypt(&aes, &iv, &plaintext);
+            assert_eq!(expected_ciphertext.len(), (length / 16 + 1) * 16);
+
+            let mut encryptor = set2::cbc::Encryptor::new(&aes, &iv);
+            let mut ciphertext = Vec::new();
+            let mut remaining = &plaintext[..];
+            while !remaining.is_empty() {
+                let (chunk, rest) = remaining.split_at(rng.gen_range(0..=remaining.len()));
+                ciphertext.extend(encryptor.update(chunk));
+                remaining = rest;
+            }
+            ciphertext.extend(encryptor.finalize());
+            assert_eq!(ciphertext, expected_ciphertext);
+
+            let mut decryptor = set2::cbc::Decryptor::new(&aes, &iv);
+            let mut decrypted = Vec::new();
+            let mut remaining = &ciphertext[..];
+            while !remaining.is_empty() {
+                let (chunk, rest) = remaining.split_at(rng.gen_range(0..=remaining.len()));
+                decrypted.extend(decryptor.update(chunk));
+                remaining = rest;
+            }
+            decrypted.extend(decryptor.finalize().unwrap());
+            assert_eq!(decrypted, plaintext);
+        }
+    }
+
+    #[test]
+    fn rank_ecb_candidates() {
+        // challenge 8's one ECB encrypted line, planted at its original position (132) among
+        // 203 random hex strings of the same length
+        let ecb_line = "d880619740a8a19b7840a8a31c810a3d08649af70dc06f4fd5d2d69c744cd283\
+                        e2dd052f6b641dbf9d11b0348542bb5708649af70dc06f4fd5d2d69c744cd283\
+                        9475c9dfdbc1d46597949d9c7e82bf5a08649af70dc06f4fd5d2d69c744cd283\
+                        97a93eab8d6aecd566489154789a6b0308649af70dc06f4fd5d2d69c744cd283\
+                        d403180c98c8f6db1f2a3f9c4040deb0ab51b29933f2c123c58386b06fba186a";
+        let mut lines: Vec<String> = (0..203)
+            .map(|_| set1::encode_hex(&set2::random_bytes(160)))
+            .collect();
+        lines.insert(132, ecb_line.to_string());
+        let hex_ciphertexts: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let ranking = set1::rank_ecb_candidates(&hex_ciphertexts);
+
+        assert_eq!(ranking.len(), 204);
+        assert_eq!(ranking[0], (132, 3));
+        assert!(ranking[1..].iter().all(|&(_, repeats)| repeats == 0));
+    }
+
+    #[test]
+    fn count_repeated_blocks() {
+        assert_eq!(set1::count_repeated_blocks(b"", 16), 0);
+        assert_eq!(set1::count_repeated_blocks(b"YELLOW SUBMARINE", 16), 0);
+        assert_eq!(set1::count_repeated_blocks(&[0; 64], 16), 3);
+        assert_eq!(set1::count_repeated_blocks(b"abcdabcdxyzwabcdxyz", 4), 2);
+    }
+
+    #[test]
+    fn set2_challenge11() {
+        let input = [b'A'; 43];
+        for _ in 0..2000 {
+            let (ciphertext, mode) = set2::encryption_oracle(&input);
+            assert_eq!(set2::detect_mode(&ciphertext), mode);
+        }
+    }
+
+    #[test]
+    fn set2_challenge12() {
+        let oracle = set2::byte_at_a_time::EcbOracle::challenge12();
+        let suffix = set2::byte_at_a_time::break_ecb(|input| oracle.encrypt(input)).unwrap();
+
+        assert_eq!(
+            String::from_utf8(suffix).unwrap(),
+            "Rollin' in my 5.0\n\
+             With my rag-top down so my hair can blow\n\
+             The girlies on standby waving just to say hi\n\
+             Did you stop? No, I just drove by\n"
+        );
+    }
+
+    #[test]
+    fn set2_challenge14() {
+        for _ in 0..5 {
+            let oracle = set2::byte_at_a_time::EcbOracle::challenge14();
+            let suffix = set2::byte_at_a_time::break_ecb(|input| oracle.encrypt(input)).unwrap();
+
+            assert!(String::from_utf8(suffix)
+                .unwrap()
+                .starts_with("Rollin' in my 5.0\n"));
+        }
+    }
+
+    #[test]
+    fn byte_at_a_time_with_other_block_sizes_and_prefixes() {
+        let suffix = b"attack at dawn, bring 2 sandwiches\x01\x01\x02";
+        for &block_size in [8, 24].iter() {
+            for prefix_length in 0..2 * block_size + 1 {
+                // a prefix that ends in the attacker's own filler bytes
+                let prefix: Vec<u8> = (0..prefix_length).map(|i| (i % 3) as u8).collect();
+                let cipher = IdentityCipher::new(&vec![0; block_size]).unwrap();
+                let oracle = set2::byte_at_a_time::EcbOracle::new(cipher, &prefix, suffix);
+                let encrypt = |input: &[u8]| oracle.encrypt(input);
+
+                assert_eq!(
+                    set2::byte_at_a_time::discover_block_size(encrypt),
+                    Ok((block_size, prefix_length + suffix.len()))
+                );
+                assert_eq!(
+                    set2::byte_at_a_time::discover_prefix_length(encrypt, block_size),
+                    Ok(prefix_length)
+                );
+                assert_eq!(
+                    set2::byte_at_a_time::break_ecb(encrypt),
+                    Ok(suffix.to_vec())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn byte_at_a_time_rejects_cbc() {
+        let aes = set1::aes::Aes::new(&set2::random_aes_key()).unwrap();
+        let iv = set2::random_bytes(16);
+        let encrypt = |input: &[u8]| set2::cbc::encrypt(&aes, &iv, &[input, b"secret"].concat());
+
+        assert!(!set2::byte_at_a_time::is_ecb(encrypt, 16));
+        assert!(set2::byte_at_a_time::break_ecb(encrypt).is_err());
+    }
+
+    #[test]
+    fn parse_and_encode_cookie() {
+        let cookie = set2::ecb_cut_and_paste::parse_cookie("foo=bar&baz=qux&zap=zazzle").unwrap();
+        assert_eq!(
+            cookie,
+            vec![
+                (String::from("foo"), String::from("bar")),
+                (String::from("baz"), String::from("qux")),
+                (String::from("zap"), String::from("zazzle")),
+            ]
+        );
+        assert_eq!(
+            set2::ecb_cut_and_paste::encode_cookie(&cookie),
+            "foo=bar&baz=qux&zap=zazzle"
+        );
+        assert!(set2::ecb_cut_and_paste::parse_cookie("foo=bar&baz").is_err());
+        assert!(set2::ecb_cut_and_paste::parse_cookie("foo=bar=baz").is_err());
+        assert_eq!(
+            set2::ecb_cut_and_paste::profile_for("foo@bar.com&role=admin"),
+            "email=foo@bar.comroleadmin&uid=10&role=user"
+        );
+    }
+
+    #[test]
+    fn set2_challenge13() {
+        let service = set2::ecb_cut_and_paste::ProfileService::new();
+        let honest = service.encrypted_profile_for("foo@bar.com");
+        assert!(!service.is_admin(&honest));
+
+        let forged = set2::ecb_cut_and_paste::forge_admin_profile(|email| {
+            service.encrypted_profile_for(email)
+        });
+        let cookie = service.decrypt_profile(&forged).unwrap();
+        assert_eq!(cookie[2], (String::from("role"), String::from("admin")));
+        assert!(service.is_admin(&forged));
+    }
+
+    // decrypts the forgery, checks everything outside its scrambled range is the expected
+    // plaintext with the token injected, and returns the scrambled bytes
+    fn check_userdata_forgery<C: set2::userdata::UserdataCipher>(
+        service: &set2::userdata::UserdataService<C>,
+        forgery: &set2::userdata::BitFlipForgery,
+        filler: usize,
+    ) -> Vec<u8>
+    where
+        C::Error: std::fmt::Debug,
+    {
+        assert!(service.is_admin(&forgery.ciphertext));
+        let plaintext = service.decrypt(&forgery.ciphertext).unwrap();
+        let expected = format!(
+            "{}{}{}{}",
+            set2::userdata::PREFIX,
+            "A".repeat(filler),
+            set2::userdata::ADMIN_TOKEN,
+            set2::userdata::SUFFIX
+        );
+        assert_eq!(plaintext.len(), expected.len());
+        for (i, (&byte, &expected_byte)) in plaintext.iter().zip(expected.as_bytes()).enumerate() {
+            if !forgery.scrambled.contains(&i) {
+                assert_eq!(byte, expected_byte, "byte {}", i);
+            }
+        }
+        plaintext[forgery.scrambled.clone()].to_vec()
+    }
+
+    #[test]
+    fn set2_challenge16() {
+        let service = set2::userdata::UserdataService::new();
+        assert!(!service.is_admin(&service.encrypt_userdata(";admin=true;")));
+
+        let forgery =
+            set2::cbc_bit_flipping::forge_admin(|userdata| service.encrypt_userdata(userdata));
+        assert_eq!(forgery.scrambled, 32..48);
+        let scrambled = check_userdata_forgery(&service, &forgery, 16);
+        assert_ne!(scrambled, vec![b'A'; 16]);
+    }
+
+    #[test]
+    fn set3_challenge17() {
+        let service = set3::padding_oracle::PaddingOracleService::new();
+        for string in set3::padding_oracle::CHALLENGE_17_STRINGS.iter() {
+            let plaintext = set1::base64_decode(string);
+            let (ciphertext, iv) = service.encrypt(&plaintext);
+            let decryption = set3::padding_oracle::decrypt(
+                |ciphertext, iv| service.has_valid_padding(ciphertext, iv),
+                &ciphertext,
+                &iv,
+                |_| {},
+            )
+            .unwrap();
+
+            assert_eq!(decryption.plaintext, plaintext);
+            assert!(decryption.query_count <= ciphertext.len() * 257);
+        }
+
+        let (ciphertext, iv) = service.encrypt_random_string();
+        let decryption = set3::padding_oracle::decrypt(
+            |ciphertext, iv| service.has_valid_padding(ciphertext, iv),
+            &ciphertext,
+            &iv,
+            |_| {},
+        )
+        .unwrap();
+        assert!(set3::padding_oracle::CHALLENGE_17_STRINGS
+            .iter()
+            .any(|string| set1::base64_decode(string) == decryption.plaintext));
+    }
+
+    #[test]
+    fn padding_oracle_every_padding_length_and_progress() {
+        // an oracle over a different block cipher, every plaintext length modulo the block size
+        // (including the ambiguous 0x02 0x02 ending) and a progress report for every block
+        let cipher = set1::aes::Aes::with_round_count(&set2::random_aes_key(), 3).unwrap();
+        let mut rng = rand::thread_rng();
+        for length in 0..48 {
+            let plaintext: Vec<u8> = (0..length).map(|_| rng.gen()).collect();
+            let iv: [u8; 16] = rng.gen();
+            let ciphertext = set2::cbc::encrypt(&cipher, &iv, &plaintext);
+            let mut reports = Vec::new();
+            let decryption = set3::padding_oracle::decrypt(
+                |ciphertext, iv| set2::cbc::decrypt(&cipher, iv, ciphertext).is_ok(),
+                &ciphertext,
+                &iv,
+                |progress| {
+                    reports.push((
+                        progress.block_index,
+                        progress.block_count,
+                        progress.plaintext_block.to_vec(),
+                        progress.query_count,
+                    ))
+                },
+            )
+            .unwrap();
+
+            assert_eq!(decryption.plaintext, plaintext);
+            let padded = set2::pkcs7::pad(&plaintext, 16);
+            assert_eq!(reports.len(), ciphertext.len() / 16);
+            for (i, (block_index, block_count, block, _)) in reports.iter().enumerate() {
+                assert_eq!((*block_index, *block_count), (i, reports.len()));
+                assert_eq!(block[..], padded[16 * i..16 * (i + 1)]);
+            }
+            assert!(reports.windows(2).all(|pair| pair[0].3 < pair[1].3));
+            assert_eq!(reports.last().unwrap().3, decryption.query_count);
+        }
+    }
+
+    #[test]
+    fn set3_challenge18() {
+        let ciphertext = set1::base64_decode(
+            "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==",
+        );
+        let aes = set1::aes::Aes::new(b"YELLOW SUBMARINE").unwrap();
+        let plaintext =
+            set3::ctr::ctr(aes, &[0; 8], CounterLayout::LittleEndian64, &ciphertext).unwrap();
+
+        assert_eq!(
+            String::from_utf8(plaintext).unwrap(),
+            "Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby "
+        );
+    }
+
+    #[test]
+    fn ctr_nist_sp_800_38a() {
+        let key = set1::decode_hex("2b7e151628aed2a6abf7158809cf4f3c");
+        let aes = set1::aes::Aes::new(&key).unwrap();
+        let plaintext = set1::decode_hex(concat!(
+            "6bc1bee22e409f96e93d7e117393172a",
+            "ae2d8a571e03ac9c9eb76fac45af8e51",
+            "30c81c46a35ce411e5fbc1191a0a52ef",
+            "f69f2445df4f9b17ad2b417be66c3710"
+        ));
+        let ciphertext = set1::decode_hex(concat!(
+            "874d6191b620e3261bef6864990db6ce",
+            "9806f66b7970fdff8617187bb9fffdff",
+            "5ae4df3edbd5d35e5b4f09020db03eab",
+            "1e031dda2fbe03d1792170a0f3009cee"
+        ));
+        let nonce = set1::decode_hex("f0f1f2f3f4f5f6f7f8f9fafb");
+        let mut keystream = set3::ctr::Keystream::new(aes, &nonce, CounterLayout::BigEndian96)
+            .unwrap()
+            .with_initial_counter(0xfcfdfeff)
+            .unwrap();
+
+        assert_eq!(
+            keystream.counter_block(0),
+            Some(set1::decode_hex("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff"))
+        );
+        assert_eq!(
+            keystream.counter_block(1),
+            Some(set1::decode_hex("f0f1f2f3f4f5f6f7f8f9fafbfcfdff00"))
+        );
+        let mut data = plaintext.clone();
+        keystream.apply_keystream(&mut data).unwrap();
+        assert_eq!(data, ciphertext);
+        keystream.seek(0);
+        keystream.apply_keystream(&mut data).unwrap();
+        assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn ctr_counter_runs_out() {
+        let aes = set1::aes::Aes::new(&set2::random_aes_key()).unwrap();
+        let keystream = set3::ctr::Keystream::new(aes, &[0; 12], CounterLayout::BigEndian96)
+            .unwrap();
+        assert!(keystream.clone().with_initial_counter(1 << 32).is_err());
+
+        // two blocks left before the 32-bit counter would wrap around to 0
+        let mut keystream = keystream.with_initial_counter(0xffff_fffe).unwrap();
+        assert_eq!(keystream.counter_block(2), None);
+        assert_eq!(keystream.clone().count(), 32);
+        let mut data = [0; 33];
+        assert!(keystream.apply_keystream(&mut data).is_err());
+        assert_eq!(data, [0; 33]);
+        keystream.apply_keystream(&mut data[..32]).unwrap();
+        assert_eq!(keystream.next(), None);
+
+        let mut keystream = set3::ctr::Keystream::new(
+            set1::aes::Aes::new(&set2::random_aes_key()).unwrap(),
+            &[0; 8],
+            CounterLayout::LittleEndian64,
+        )
+        .unwrap()
+        .with_initial_counter(u64::MAX)
+        .unwrap();
+        keystream.seek(16);
+        assert_eq!(keystream.next(), None);
+    }
+
+    #[test]
+    fn ctr_keystream_seeking() {
+        let aes = set1::aes::Aes::new(&set2::random_aes_key()).unwrap();
+        let mut rng = rand::thread_rng();
+        for &layout in [CounterLayout::LittleEndian64, CounterLayout::BigEndian96].iter() {
+            let nonce = set2::random_bytes(layout.nonce_length());
+            let mut keystream = set3::ctr::Keystream::new(aes.clone(), &nonce, layout).unwrap();
+            let full: Vec<u8> = keystream.clone().take(100).collect();
+
+            for _ in 0..50 {
+                let offset = rng.gen_range(0..100);
+                keystream.seek(offset as u64);
+                let partial: Vec<u8> = keystream.by_ref().take(100 - offset).collect();
+                assert_eq!(partial, full[offset..]);
+                assert_eq!(keystream.position(), 100);
+            }
+
+            // decrypting a slice in the middle of a message
+            let plaintext = set2::random_bytes(100);
+            let ciphertext = set1::fixed_xor(&plaintext, &full);
+            let mut middle = ciphertext[37..61].to_vec();
+            keystream.seek(37);
+            keystream.apply_keystream(&mut middle).unwrap();
+            assert_eq!(middle, plaintext[37..61]);
+        }
+        assert!(
+            set3::ctr::Keystream::new(aes.clone(), &[0; 12], CounterLayout::LittleEndian64)
+                .is_err()
+        );
+    }
+
+    fn encrypt_under_fixed_nonce(plaintexts: &[Vec<u8>]) -> (Vec<Vec<u8>>, Vec<u8>) {
+        let aes = set1::aes::Aes::new(&set2::random_aes_key()).unwrap();
+        let keystream: Vec<u8> =
+            set3::ctr::Keystream::new(aes, &[0; 8], CounterLayout::LittleEndian64)
+                .unwrap()
+                .take(plaintexts.iter().map(Vec::len).max().unwrap())
+                .collect();
+        let ciphertexts = plaintexts
+            .iter()
+            .map(|plaintext| set1::fixed_xor(plaintext, &keystream[..plaintext.len()]))
+            .collect();
+        (ciphertexts, keystream)
+    }
+
+    fn known_bytes(bytes: &[u8]) -> Vec<Option<u8>> {
+        bytes.iter().map(|&byte| Some(byte)).collect()
+    }
+
+    #[test]
+    fn set3_challenge19() {
+        let contents = read_to_string("resources/s3c19.txt").unwrap();
+        let plaintexts: Vec<Vec<u8>> = contents.lines().map(set1::base64_decode).collect();
+        let (ciphertexts, keystream) = encrypt_under_fixed_nonce(&plaintexts);
+
+        let mut recovery = KeystreamRecovery::statistical(&ciphertexts, Alignment::Longest);
+        let correct = recovery
+            .keystream()
+            .iter()
+            .zip(&keystream)
+            .filter(|(&guess, &actual)| guess == Some(actual))
+            .count();
+        // the first column (capitals) and the last few (only a couple of lines are that long)
+        // are usually wrong, the longest line is a good candidate to fix them by hand
+        assert!(correct >= keystream.len() * 2 / 3);
+        let longest = (0..ciphertexts.len())
+            .max_by_key(|&row| ciphertexts[row].len())
+            .unwrap();
+        recovery
+            .guess_plaintext(longest, 0, b"He, too, has been changed in his turn,")
+            .unwrap();
+        assert_eq!(recovery.keystream(), &known_bytes(&keystream)[..]);
+        assert_eq!(recovery.plaintexts(), plaintexts);
+        assert!(recovery.guess_plaintext(plaintexts.len(), 0, b"I").is_err());
+        assert!(recovery.guess_plaintext(0, 30, b"day?").is_err());
+    }
+
+    #[test]
+    fn fixed_nonce_ctr_truncated_to_shortest() {
+        // challenge 20's method, run on the lines of the challenge 6 lyrics: its own 20.txt
+        // isn't vendored
+        let contents = read_to_string("resources/s1c6_solution.txt").unwrap();
+        let plaintexts: Vec<Vec<u8>> = contents
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.as_bytes().to_vec())
+            .collect();
+        let (ciphertexts, keystream) = encrypt_under_fixed_nonce(&plaintexts);
+
+        let mut recovery =
+            KeystreamRecovery::statistical(&ciphertexts, Alignment::TruncateToShortest);
+        let shortest = plaintexts.iter().map(Vec::len).min().unwrap();
+        assert_eq!(recovery.keystream(), &known_bytes(&keystream[..shortest])[..]);
+        for (recovered, plaintext) in recovery.plaintexts().iter().zip(&plaintexts) {
+            assert_eq!(recovered[..], plaintext[..shortest]);
+        }
+
+        // a guess past the truncated length leaves a gap, the plaintexts stop where it starts
+        let longest = (0..plaintexts.len())
+            .max_by_key(|&row| plaintexts[row].len())
+            .unwrap();
+        let offset = shortest + 4;
+        recovery
+            .guess_plaintext(longest, offset, &plaintexts[longest][offset..])
+            .unwrap();
+        assert_eq!(recovery.keystream()[shortest..offset], [None; 4]);
+        assert_eq!(
+            recovery.keystream()[offset..],
+            known_bytes(&keystream[offset..plaintexts[longest].len()])[..]
+        );
+        for (recovered, plaintext) in recovery.plaintexts().iter().zip(&plaintexts) {
+            assert_eq!(recovered[..], plaintext[..shortest]);
+        }
+
+        // filling the gap in makes the rest of the longest line readable
+        recovery
+            .guess_plaintext(longest, shortest, &plaintexts[longest][shortest..offset])
+            .unwrap();
+        assert_eq!(recovery.plaintexts()[longest], plaintexts[longest]);
+    }
+
+    #[test]
+    fn set3_challenge21() {
+        // outputs of mt19937ar.c's main(), and the C++ standard's check on the 10000th output
+        let mut mt = set3::mt19937::Mt19937::default();
+        mt.init_by_array(&[0x123, 0x234, 0x345, 0x456]);
+        let outputs: Vec<u32> = (0..5).map(|_| mt.next_u32()).collect();
+        assert_eq!(
+            outputs,
+            [1067595299, 955945823, 477289528, 4107218783, 4228976476]
+        );
+
+        let mut mt = set3::mt19937::Mt19937::default();
+        assert_eq!((0..10000).map(|_| mt.next_u32()).last(), Some(4123659995));
+
+        let mut mt = set3::mt19937::Mt19937::new(42);
+        let outputs: Vec<u32> = (0..3).map(|_| mt.next_u32()).collect();
+        assert_eq!(outputs, [1608637542, 3421126067, 4083286876]);
+    }
+
+    #[test]
+    fn mt19937_64() {
+        let mut mt = set3::mt19937::Mt19937_64::default();
+        mt.init_by_array(&[0x12345, 0x23456, 0x34567, 0x45678]);
+        let outputs: Vec<u64> = (0..5).map(|_| mt.next_u64()).collect();
+        assert_eq!(
+            outputs,
+            [
+                7266447313870364031,
+                4946485549665804864,
+                16945909448695747420,
+                16394063075524226720,
+                4873882236456199058
+            ]
+        );
+
+        let mut mt = set3::mt19937::Mt19937_64::default();
+        assert_eq!(
+            (0..10000).map(|_| mt.next_u64()).last(),
+            Some(9981545732273789042)
+        );
+
+        let mut mt = set3::mt19937::Mt19937_64::new(42);
+        let outputs: Vec<u64> = (0..3).map(|_| mt.next_u64()).collect();
+        assert_eq!(
+            outputs,
+            [
+                13930160852258120406,
+                11788048577503494824,
+                13874630024467741450
+            ]
+        );
+    }
+
+    #[test]
+    fn mt19937_as_rng_core() {
+        use rand::{RngCore, SeedableRng};
+
+        let mut mt = set3::mt19937::Mt19937::from_seed(42u32.to_le_bytes());
+        let mut reference = set3::mt19937::Mt19937::new(42);
+        let mut bytes = [0; 6];
+        mt.fill_bytes(&mut bytes);
+        let first = reference.next_u32().to_le_bytes();
+        let second = reference.next_u32().to_le_bytes();
+        assert_eq!(bytes, [first[0], first[1], first[2], first[3], second[0], second[1]]);
+
+        // works with anything built on rand's traits
+        let mut mt = set3::mt19937::Mt19937_64::seed_from_u64(1);
+        let roll = mt.gen_range(1..=6);
+        assert!((1..=6).contains(&roll));
+    }
+
+    #[test]
+    fn set3_challenge23() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let y: u32 = rng.gen();
+            assert_eq!(set3::mt19937::untemper(set3::mt19937::temper(y)), y);
+        }
+
+        let mut mt = set3::mt19937::Mt19937::new(rng.gen());
+        let mut outputs = [0; 624];
+        for output in outputs.iter_mut() {
+            *output = mt.next_u32();
+        }
+        let mut clone = set3::mt19937::Mt19937::clone_from_outputs(&outputs);
+        for _ in 0..2000 {
+            assert_eq!(clone.next_u32(), mt.next_u32());
+        }
+    }
+
+    #[test]
+    fn gf2_linear_system() {
+        use set3::gf2::{BitVector, LinearSystem};
+
+        let equation = |bits: &[usize]| {
+            let mut coefficients = BitVector::zeros(3);
+            for &bit in bits {
+                coefficients.set(bit, true);
+            }
+            coefficients
+        };
+        // x0 + x1 = 1, x1 + x2 = 0, x0 + x2 = 1 (redundant), x2 = 1
+        let mut system = LinearSystem::new(3);
+        assert!(system.add_equation(equation(&[0, 1]), true));
+        assert!(system.add_equation(equation(&[1, 2]), false));
+        assert!(!system.add_equation(equation(&[0, 2]), true));
+        assert_eq!(system.rank(), 2);
+        assert_eq!(system.solve(), Some(equation(&[0])));
+        assert!(system.add_equation(equation(&[2]), true));
+        assert_eq!(system.solve(), Some(equation(&[1, 2])));
+
+        assert!(!system.add_equation(equation(&[0, 1, 2]), true));
+        assert_eq!(system.solve(), None);
+    }
+
+    #[test]
+    fn mt19937_clone_from_truncated_outputs() {
+        let mut mt = set3::mt19937::Mt19937::new(rand::thread_rng().gen());
+        let outputs: Vec<u32> = (0..624 * 2).map(|_| mt.next_u32() & 0xffff_fff0).collect();
+
+        assert!(
+            set3::mt19937::Mt19937::clone_from_truncated_outputs(&outputs[..900], 0xffff_fff0)
+                .is_err()
+        );
+        let mut clone =
+            set3::mt19937::Mt19937::clone_from_truncated_outputs(&outputs, 0xffff_fff0).unwrap();
+        for _ in 0..2000 {
+            assert_eq!(clone.next_u32(), mt.next_u32());
+        }
+    }
+
+    #[test]
+    fn mt19937_clone_from_top_byte_of_outputs() {
+        let mut mt = set3::mt19937::Mt19937::new(rand::thread_rng().gen());
+        let outputs: Vec<u32> = (0..624 * 4).map(|_| mt.next_u32() >> 24 << 24).collect();
+
+        let mut clone =
+            set3::mt19937::Mt19937::clone_from_truncated_outputs(&outputs, 0xff00_0000).unwrap();
+        for _ in 0..2000 {
+            assert_eq!(clone.next_u32(), mt.next_u32());
+        }
+    }
+
+    #[test]
+    fn set3_challenge22() {
+        use set3::timestamp_seed::{self, Clock, SeedMatch, SimulatedClock};
+
+        let clock = SimulatedClock::new(1_700_000_000);
+        let start = clock.now() as u32;
+        let output = timestamp_seed::first_output_after_waiting(&clock);
+        let seeds = timestamp_seed::recent_seeds(&clock, 2000);
+        let found = timestamp_seed::recover_seed(&[output], seeds, 0).unwrap();
+        assert_eq!(found.offset, 0);
+        assert!(found.seed >= start + 40 && found.seed <= start + 1000);
+        assert_eq!(set3::mt19937::Mt19937::new(found.seed).next_u32(), output);
+
+        // a few outputs seen somewhere in the middle of a token generator's stream
+        let seed = clock.now() as u32 - 500;
+        let mut mt = set3::mt19937::Mt19937::new(seed);
+        for _ in 0..37 {
+            mt.next_u32();
+        }
+        let observed: Vec<u32> = (0..3).map(|_| mt.next_u32()).collect();
+        let seeds = timestamp_seed::recent_seeds(&clock, 1000);
+        assert_eq!(
+            timestamp_seed::recover_seed(&observed, seeds, 100),
+            Some(SeedMatch { seed, offset: 37 })
+        );
+        let seeds = timestamp_seed::recent_seeds(&clock, 1000);
+        assert_eq!(timestamp_seed::recover_seed(&observed, seeds, 10), None);
+        let seeds = timestamp_seed::recent_seeds(&clock, 100);
+        assert_eq!(timestamp_seed::recover_seed(&observed, seeds, 100), None);
+    }
+
+    #[test]
+    fn set3_challenge24() {
+        use set3::mt19937_cipher;
+        use set3::timestamp_seed::{Clock, SimulatedClock};
+
+        let plaintext = b"the quick brown fox";
+        let ciphertext = mt19937_cipher::apply_keystream(1234, plaintext);
+        assert_ne!(&ciphertext[..], &plaintext[..]);
+        assert_eq!(mt19937_cipher::apply_keystream(1234, &ciphertext), plaintext);
+
+        let seed: u16 = rand::thread_rng().gen();
+        let ciphertext = mt19937_cipher::encrypt_with_random_prefix(seed, &[b'A'; 14]);
+        assert_eq!(mt19937_cipher::recover_seed(&ciphertext, &[b'A'; 14]), Some(seed));
+        assert_eq!(mt19937_cipher::recover_seed(&ciphertext, &[]), None);
+
+        let clock = SimulatedClock::new(1_700_000_000);
+        let token = mt19937_cipher::password_reset_token(&clock);
+        clock.sleep(300);
+        assert!(mt19937_cipher::is_time_seeded_token(&token, &clock, 600));
+        assert!(!mt19937_cipher::is_time_seeded_token(&token, &clock, 200));
+        assert!(!mt19937_cipher::is_time_seeded_token(
+            &set2::random_bytes(16),
+            &clock,
+            600
+        ));
+        assert!(!mt19937_cipher::is_time_seeded_token(&[], &clock, 600));
+    }
+
+    #[test]
+    fn set4_challenge25() {
+        use set4::random_access_ctr::{self, EditService};
+
+        let contents_s1c7 = read_to_string("resources/s1c7_no_newlines.txt").unwrap();
+        let key: &[u8; 16] = arrayref::array_ref!("YELLOW SUBMARINE".as_bytes(), 0, 16);
+        let padded = set1::aes::aes_128_ecb(&set1::base64_decode(&contents_s1c7), key);
+        let plaintext = set2::pkcs7::unpad(&padded, 16).unwrap();
+
+        let service = EditService::new();
+        let ciphertext = service.encrypt(&plaintext);
+        let recovered =
+            random_access_ctr::recover_plaintext(&ciphertext, |c, o, n| service.edit(c, o, n));
+        assert_eq!(recovered, Ok(plaintext.clone()));
+
+        // edits in the middle only touch the edited bytes, and edits at the end extend
+        let edited = service.edit(&ciphertext, 100, b"hello").unwrap();
+        assert_eq!(edited[..100], ciphertext[..100]);
+        assert_eq!(edited[105..], ciphertext[105..]);
+        let edited = service.edit(&edited, plaintext.len(), b"!!").unwrap();
+        let mut expected = plaintext.clone();
+        expected[100..105].copy_from_slice(b"hello");
+        expected.extend_from_slice(b"!!");
+        let recovered =
+            random_access_ctr::recover_plaintext(&edited, |c, o, n| service.edit(c, o, n));
+        assert_eq!(recovered, Ok(expected));
+        assert!(service.edit(&ciphertext, plaintext.len() + 1, b"!").is_err());
+    }
+
+    #[test]
+    fn set4_challenge26() {
+        use set2::userdata::UserdataService;
+        use set4::ctr_bit_flipping::{self, CtrCipher};
+
+        let cbc = UserdataService::new();
+        let ctr = UserdataService::with_cipher(CtrCipher::new());
+        assert!(!ctr.is_admin(&ctr.encrypt_userdata(";admin=true;")));
+
+        // CBC sacrifices a whole block of plaintext to the flips
+        let forgery =
+            set2::cbc_bit_flipping::forge_admin(|userdata| cbc.encrypt_userdata(userdata));
+        let scrambled = check_userdata_forgery(&cbc, &forgery, 16);
+        assert_eq!(forgery.scrambled.len(), 16);
+        assert_ne!(scrambled, vec![b'A'; 16]);
+
+        // CTR doesn't, and needs no filler to align the placeholder either
+        let forgery = ctr_bit_flipping::forge_admin(|userdata| ctr.encrypt_userdata(userdata));
+        let scrambled = check_userdata_forgery(&ctr, &forgery, 0);
+        assert!(forgery.scrambled.is_empty());
+        assert!(scrambled.is_empty());
+
+        // and each forgery is meaningless to the other service
+        assert!(!cbc.is_admin(&forgery.ciphertext));
+    }
+
+    #[test]
+    fn set4_challenge27() {
+        use set2::userdata::{UserdataCipher, UserdataService};
+        use set4::cbc_key_as_iv::{self, DecryptionError, KeyAsIvCipher};
+
+        let key = set2::random_aes_key();
+        let service = UserdataService::with_cipher(KeyAsIvCipher::with_key(key));
+        let ciphertext = service.encrypt_userdata("hello");
+        assert!(service.decrypt(&ciphertext).is_ok());
+        assert_eq!(
+            service.decrypt(&ciphertext[..20]),
+            Err(DecryptionError::Padding(PaddingError::BadLength))
+        );
+
+        let recovered = cbc_key_as_iv::recover_key(&ciphertext, |c| service.decrypt(c));
+        assert_eq!(recovered, Ok(key));
+
+        // with the key, anything can be encrypted
+        let forged = KeyAsIvCipher::with_key(recovered.unwrap()).encrypt(b";admin=true;");
+        assert!(service.is_admin(&forged));
+    }
+
+    // (message, digest) pairs from a NIST .rsp file of byte-oriented hash vectors
+    fn read_hash_vectors(path: &str) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let contents = read_to_string(path).unwrap();
+        let mut vectors = Vec::new();
+        let mut length = 0;
+        let mut message = Vec::new();
+        for line in contents.lines() {
+            let mut fields = line.splitn(2, " = ");
+            match (fields.next(), fields.next()) {
+                (Some("Len"), Some(value)) => length = value.parse::<usize>().unwrap() / 8,
+                (Some("Msg"), Some(value)) => message = set1::decode_hex(value)[..length].to_vec(),
+                (Some("MD"), Some(value)) => {
+                    vectors.push((message.clone(), set1::decode_hex(value)))
+                }
+                _ => {}
+            }
+        }
+        vectors
+    }
+
+    #[test]
+    fn sha1_fips_180_vectors() {
+        use set4::merkle_damgard::Hasher;
+        use set4::sha1::{self, Sha1};
+
+        assert_eq!(
+            sha1::sha1(b"abc").to_vec(),
+            set1::decode_hex("a9993e364706816aba3e25717850c26c9cd0d89d")
+        );
+        assert_eq!(
+            sha1::sha1(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq").to_vec(),
+            set1::decode_hex("84983e441c3bd26ebaae4aa1f95129e5e54670f1")
+        );
+        let mut million_as = Hasher::<Sha1>::new();
+        for _ in 0..1000 {
+            million_as.update(&[b'a'; 1000]);
+        }
+        assert_eq!(
+            million_as.finalize().to_vec(),
+            set1::decode_hex("34aa973cd4c4daa4f61eeb2bdbad27316534016f")
+        );
+
+        // the NIST SHAVS (CAVS 11.0) byte-oriented short and long message files
+        let mut vectors = read_hash_vectors("resources/SHA1ShortMsg.rsp");
+        vectors.extend(read_hash_vectors("resources/SHA1LongMsg.rsp"));
+        assert_eq!(vectors.len(), 65 + 64);
+        for (message, digest) in vectors {
+            assert_eq!(sha1::sha1(&message).to_vec(), digest);
+            // in uneven pieces
+            let mut sha1 = Hasher::<Sha1>::new();
+            for chunk in message.chunks(23) {
+                sha1.update(chunk);
+            }
+            assert_eq!(sha1.finalize().to_vec(), digest);
+        }
+    }
+
+    #[test]
+    fn sha1_resumes_from_state() {
+        use set4::merkle_damgard::{Hasher, MerkleDamgard};
+        use set4::sha1::{self, Sha1};
+
+        let message = [b'x'; 200];
+        let mut sha1 = Hasher::<Sha1>::new();
+        sha1.update(&message[..128]);
+        assert_eq!(sha1.length(), 128);
+        let mut resumed = Hasher::<Sha1>::from_state(sha1.state(), 128);
+        resumed.update(&message[128..]);
+        assert_eq!(resumed.finalize(), sha1::sha1(&message));
+
+        // the digest is the state after the padding
+        let digest = sha1::sha1(&message[..100]);
+        let mut padded = message[..100].to_vec();
+        padded.extend(Sha1::padding(100));
+        assert_eq!(padded.len(), 128);
+        let mut sha1 = Hasher::<Sha1>::new();
+        sha1.update(&padded);
+        assert_eq!(sha1.state(), Sha1::state_from_digest(&digest));
+    }
+
+    #[test]
+    fn set4_challenge28() {
+        use set4::sha1;
+
+        let key = set2::random_bytes(16);
+        let message = b"comment1=cooking%20MCs;userdata=foo";
+        let mac = sha1::secret_prefix_mac(&key, message);
+        assert!(sha1::verify_secret_prefix_mac(&key, message, &mac));
+        let other_message = b"comment1=cooking%20MCs;userdata=bar";
+        assert!(!sha1::verify_secret_prefix_mac(&key, other_message, &mac));
+        assert!(!sha1::verify_secret_prefix_mac(&set2::random_bytes(16), message, &mac));
+        let mut tampered = mac;
+        tampered[0] ^= 1;
+        assert!(!sha1::verify_secret_prefix_mac(&key, message, &tampered));
+    }
+
+    #[test]
+    fn sha2_fips_180_vectors() {
+        use set4::merkle_damgard::Hasher;
+        use set4::sha256::{self, Sha256};
+        use set4::sha512;
+
+        let messages: [&[u8]; 3] = [
+            b"abc",
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+        ];
+        let sha256_digests = [
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+        ];
+        let sha512_digests = [
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            "204a8fc6dda82f0a0ced7beb8e08a41657c16ef468b228a8279be331a703c33596fd15c13b1b07f9aa1d3bea57789ca031ad85c7a71dd70354ec631238ca3445",
+            "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+        ];
+        for ((message, sha256_digest), sha512_digest) in
+            messages.iter().zip(&sha256_digests).zip(&sha512_digests)
+        {
+            assert_eq!(sha256::sha256(message).to_vec(), set1::decode_hex(sha256_digest));
+            assert_eq!(sha512::sha512(message).to_vec(), set1::decode_hex(sha512_digest));
+        }
+
+        let mut million_as = Hasher::<Sha256>::new();
+        for _ in 0..1000 {
+            million_as.update(&[b'a'; 1000]);
+        }
+        assert_eq!(
+            million_as.finalize().to_vec(),
+            set1::decode_hex("cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0")
+        );
+    }
+
+    #[test]
+    fn merkle_damgard_padding() {
+        use set4::merkle_damgard::MerkleDamgard;
+        use set4::sha1::Sha1;
+        use set4::sha512::Sha512;
+
+        for length in 0..300 {
+            assert_eq!((length + Sha1::padding(length as u64).len()) % 64, 0);
+            assert_eq!((length + Sha512::padding(length as u64).len()) % 128, 0);
+        }
+        // 55 bytes leave just enough room in the block for 0x80 and the length
+        assert_eq!(Sha1::padding(55), [&[0x80][..], &[0, 0, 0, 0, 0, 0, 1, 0xb8]].concat());
+        assert_eq!(Sha1::padding(56).len(), 72);
+        assert_eq!(Sha512::padding(3).len(), 125);
+        assert_eq!(Sha512::padding(3)[109..], [[0; 15].to_vec(), vec![24]].concat()[..]);
+    }
+
+    // forge an ;admin=true extension against a secret-prefix MAC under H with an unknown key length
+    fn check_length_extension<H: set4::merkle_damgard::MerkleDamgard>() {
+        use set4::merkle_damgard;
+
+        let key = set2::random_bytes(rand::thread_rng().gen_range(1..=40));
+        let original: &[u8] =
+            b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+        let mac = merkle_damgard::secret_prefix_mac::<H>(&key, original);
+        let verify = |message: &[u8], mac: &H::Digest| {
+            merkle_damgard::secret_prefix_mac::<H>(&key, message) == *mac
+        };
+
+        let forgery = merkle_damgard::forge::<H, _>(original, &mac, b";admin=true", 64, verify)
+            .expect("no key length up to 64 worked");
+        assert_eq!(forgery.key_length, key.len());
+        assert!(forgery.message.starts_with(original));
+        assert!(forgery.message.ends_with(b";admin=true"));
+        assert!(verify(&forgery.message, &forgery.mac));
+
+        assert_eq!(
+            merkle_damgard::forge::<H, _>(original, &mac, b";admin=true", key.len() - 1, verify),
+            None
+        );
+    }
+
+    #[test]
+    fn set4_challenge29() {
+        check_length_extension::<set4::sha1::Sha1>();
+        check_length_extension::<set4::sha256::Sha256>();
+        check_length_extension::<set4::sha512::Sha512>();
+    }
+
+    #[test]
+    fn md4_rfc_1320_suite() {
+        use set4::md4::{self, Md4};
+        use set4::merkle_damgard::{Hasher, MerkleDamgard};
+
+        let suite: [(&str, &str); 7] = [
+            ("", "31d6cfe0d16ae931b73c59d7e0c089c0"),
+            ("a", "bde52cb31de33e46245e05fbdbd6fb24"),
+            ("abc", "a448017aaf21d8525fc10ae87aa6729d"),
+            ("message digest", "d9130a8164549fe818874806e1c7014b"),
+            ("abcdefghijklmnopqrstuvwxyz", "d79e1c308aa5bbcdeea8ed63df412da9"),
+            (
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "043f8582f241db351ce627e153e7f0e4",
+            ),
+            (
+                "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "e33b4ddc9c38f2199c3e7b164fcc0536",
+            ),
+        ];
+        for (message, digest) in suite.iter() {
+            assert_eq!(md4::md4(message.as_bytes()).to_vec(), set1::decode_hex(digest));
+            let mut md4 = Hasher::<Md4>::new();
+            for byte in message.bytes() {
+                md4.update(&[byte]);
+            }
+            assert_eq!(md4.finalize().to_vec(), set1::decode_hex(digest));
+        }
+
+        // resuming from the state after the first block
+        let message = suite[6].0.as_bytes();
+        let mut md4 = Hasher::<Md4>::new();
+        md4.update(&message[..64]);
+        let mut resumed = Hasher::<Md4>::from_state(md4.state(), md4.length());
+        resumed.update(&message[64..]);
+        assert_eq!(resumed.finalize(), md4::md4(message));
+
+        // resuming from a digest, whose words are little-endian unlike SHA's
+        let digest = md4::md4(b"abc");
+        assert_eq!(
+            Md4::state_from_digest(&digest),
+            [0x7a01_48a4, 0x52d8_21af, 0xe80a_c15f, 0x9d72_a67a]
+        );
+        let glue = Md4::padding(3);
+        let mut resumed = Hasher::<Md4>::from_digest(&digest, (3 + glue.len()) as u64);
+        resumed.update(b"def");
+        assert_eq!(resumed.finalize(), md4::md4(&[&b"abc"[..], &glue, b"def"].concat()));
+    }
+
+    #[test]
+    fn set4_challenge30() {
+        check_length_extension::<set4::md4::Md4>();
+
+        let key = set2::random_bytes(16);
+        let mac = set4::md4::secret_prefix_mac(&key, b"message");
+        assert_eq!(mac, set4::md4::md4(&[&key[..], b"message"].concat()));
+    }
 }
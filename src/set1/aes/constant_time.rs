@@ -0,0 +1,110 @@
+// AES without secret dependent table lookups or branches: the S-box is computed from the
+// multiplicative inverse in GF(2^8) followed by the affine transformation (FIPS-197 section 5.1.1)
+use super::Block;
+
+type State = [u8; 16];
+
+// multiplication by x, masking instead of branching on the top bit
+fn xtime(byte: u8) -> u8 {
+    (byte << 1) ^ (0x1b & 0u8.wrapping_sub(byte >> 7))
+}
+
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+// x^254, which is x^-1 for every non-zero x and maps 0 to 0
+fn gf_inverse(x: u8) -> u8 {
+    let x2 = gf_mul(x, x);
+    let x3 = gf_mul(x2, x);
+    let x6 = gf_mul(x3, x3);
+    let x12 = gf_mul(x6, x6);
+    let x15 = gf_mul(x12, x3);
+    let x30 = gf_mul(x15, x15);
+    let x60 = gf_mul(x30, x30);
+    let x120 = gf_mul(x60, x60);
+    let x240 = gf_mul(x120, x120);
+    let x252 = gf_mul(x240, x12);
+    gf_mul(x252, x2)
+}
+
+pub(super) fn s_box(byte: u8) -> u8 {
+    let b = gf_inverse(byte);
+    b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63
+}
+
+fn inverse_s_box(byte: u8) -> u8 {
+    gf_inverse(byte.rotate_left(1) ^ byte.rotate_left(3) ^ byte.rotate_left(6) ^ 0x05)
+}
+
+fn add_round_key(state: &mut State, round_key: &Block) {
+    for (byte, round_key_byte) in state.iter_mut().zip(round_key.as_slice()) {
+        *byte ^= round_key_byte;
+    }
+}
+
+fn sub_bytes(state: &mut State, s_box: fn(u8) -> u8) {
+    for byte in state.iter_mut() {
+        *byte = s_box(*byte);
+    }
+}
+
+// the state is stored column by column, like the input block
+fn shift_rows(state: &State, column: fn(usize, usize) -> usize) -> State {
+    let mut shifted = [0; 16];
+    for c in 0..4 {
+        for row in 0..4 {
+            shifted[4 * c + row] = state[4 * column(c, row) + row];
+        }
+    }
+    shifted
+}
+
+fn mix_columns(state: &mut State, coefficients: [u8; 4]) {
+    for column in state.chunks_mut(4) {
+        let original = [column[0], column[1], column[2], column[3]];
+        for row in 0..4 {
+            column[row] = (0..4).fold(0, |acc, i| {
+                acc ^ gf_mul(coefficients[(4 + i - row) % 4], original[i])
+            });
+        }
+    }
+}
+
+pub(super) fn cipher(input: &[u8; 16], round_keys: &[Block]) -> [u8; 16] {
+    let round_count = round_keys.len() - 1;
+    let column = |c, row| (c + row) % 4;
+    let mut state = *input;
+    add_round_key(&mut state, &round_keys[0]);
+    for (round, round_key) in round_keys.iter().enumerate().skip(1) {
+        sub_bytes(&mut state, s_box);
+        state = shift_rows(&state, column);
+        if round != round_count {
+            mix_columns(&mut state, [0x02, 0x03, 0x01, 0x01]);
+        }
+        add_round_key(&mut state, round_key);
+    }
+    state
+}
+
+pub(super) fn inv_cipher(input: &[u8; 16], round_keys: &[Block]) -> [u8; 16] {
+    let round_count = round_keys.len() - 1;
+    let column = |c, row| (c + 4 - row) % 4;
+    let mut state = *input;
+    add_round_key(&mut state, &round_keys[round_count]);
+    for round in (0..round_count).rev() {
+        state = shift_rows(&state, column);
+        sub_bytes(&mut state, inverse_s_box);
+        add_round_key(&mut state, &round_keys[round]);
+        if round != 0 {
+            mix_columns(&mut state, [0x0e, 0x0b, 0x0d, 0x09]);
+        }
+    }
+    state
+}
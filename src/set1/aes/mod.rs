@@ -0,0 +1,274 @@
+use super::block_cipher::{self, BlockCipher};
+use nalgebra::Matrix4;
+
+mod constant_time;
+mod t_table;
+
+type Block = Matrix4<u8>;
+const S_BOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+const INVERSE_S_BOX: [u8; 256] = [
+    0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3, 0xd7, 0xfb,
+    0x7c, 0xe3, 0x39, 0x82, 0x9b, 0x2f, 0xff, 0x87, 0x34, 0x8e, 0x43, 0x44, 0xc4, 0xde, 0xe9, 0xcb,
+    0x54, 0x7b, 0x94, 0x32, 0xa6, 0xc2, 0x23, 0x3d, 0xee, 0x4c, 0x95, 0x0b, 0x42, 0xfa, 0xc3, 0x4e,
+    0x08, 0x2e, 0xa1, 0x66, 0x28, 0xd9, 0x24, 0xb2, 0x76, 0x5b, 0xa2, 0x49, 0x6d, 0x8b, 0xd1, 0x25,
+    0x72, 0xf8, 0xf6, 0x64, 0x86, 0x68, 0x98, 0x16, 0xd4, 0xa4, 0x5c, 0xcc, 0x5d, 0x65, 0xb6, 0x92,
+    0x6c, 0x70, 0x48, 0x50, 0xfd, 0xed, 0xb9, 0xda, 0x5e, 0x15, 0x46, 0x57, 0xa7, 0x8d, 0x9d, 0x84,
+    0x90, 0xd8, 0xab, 0x00, 0x8c, 0xbc, 0xd3, 0x0a, 0xf7, 0xe4, 0x58, 0x05, 0xb8, 0xb3, 0x45, 0x06,
+    0xd0, 0x2c, 0x1e, 0x8f, 0xca, 0x3f, 0x0f, 0x02, 0xc1, 0xaf, 0xbd, 0x03, 0x01, 0x13, 0x8a, 0x6b,
+    0x3a, 0x91, 0x11, 0x41, 0x4f, 0x67, 0xdc, 0xea, 0x97, 0xf2, 0xcf, 0xce, 0xf0, 0xb4, 0xe6, 0x73,
+    0x96, 0xac, 0x74, 0x22, 0xe7, 0xad, 0x35, 0x85, 0xe2, 0xf9, 0x37, 0xe8, 0x1c, 0x75, 0xdf, 0x6e,
+    0x47, 0xf1, 0x1a, 0x71, 0x1d, 0x29, 0xc5, 0x89, 0x6f, 0xb7, 0x62, 0x0e, 0xaa, 0x18, 0xbe, 0x1b,
+    0xfc, 0x56, 0x3e, 0x4b, 0xc6, 0xd2, 0x79, 0x20, 0x9a, 0xdb, 0xc0, 0xfe, 0x78, 0xcd, 0x5a, 0xf4,
+    0x1f, 0xdd, 0xa8, 0x33, 0x88, 0x07, 0xc7, 0x31, 0xb1, 0x12, 0x10, 0x59, 0x27, 0x80, 0xec, 0x5f,
+    0x60, 0x51, 0x7f, 0xa9, 0x19, 0xb5, 0x4a, 0x0d, 0x2d, 0xe5, 0x7a, 0x9f, 0x93, 0xc9, 0x9c, 0xef,
+    0xa0, 0xe0, 0x3b, 0x4d, 0xae, 0x2a, 0xf5, 0xb0, 0xc8, 0xeb, 0xbb, 0x3c, 0x83, 0x53, 0x99, 0x61,
+    0x17, 0x2b, 0x04, 0x7e, 0xba, 0x77, 0xd6, 0x26, 0xe1, 0x69, 0x14, 0x63, 0x55, 0x21, 0x0c, 0x7d,
+];
+
+pub const BLOCK_SIZE: usize = 16;
+
+// Rcon[1..=count]: successive powers of x in GF(2^8), in the top byte of a word
+pub fn round_constants(count: usize) -> Vec<u32> {
+    let mut top_byte = 1;
+    (0..count)
+        .map(|_| {
+            let round_constant = (top_byte as u32) << 24;
+            top_byte = xtime(top_byte);
+            round_constant
+        })
+        .collect()
+}
+
+// expand the cipher key into round_count + 1 round keys (FIPS-197 section 5.2)
+fn key_expansion(key: &[u8], round_count: usize) -> Vec<Block> {
+    // key length in 32 bit words (Nk)
+    let n = key.len() / 4;
+    let word_count = 4 * (round_count + 1);
+    let round_constants = round_constants(word_count / n);
+    let mut round_keys_u32: Vec<u32> = Vec::with_capacity(word_count);
+    for word in key.chunks(4) {
+        round_keys_u32.push(u32::from_be_bytes(*arrayref::array_ref!(word, 0, 4)));
+    }
+    for i in n..word_count {
+        let previous = round_keys_u32[i - 1];
+        let temp = match i {
+            i if i % n == 0 => sub_word(previous.rotate_left(8)) ^ round_constants[i / n - 1],
+            i if n > 6 && i % n == 4 => sub_word(previous),
+            _ => previous,
+        };
+        round_keys_u32.push(round_keys_u32[i - n] ^ temp);
+    }
+    round_keys_u32
+        .chunks(4)
+        .map(|words| {
+            let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_be_bytes()).collect();
+            Block::from_column_slice(&bytes)
+        })
+        .collect()
+}
+
+fn s_box(byte: u8) -> u8 {
+    S_BOX[byte as usize]
+}
+
+fn inverse_s_box(byte: u8) -> u8 {
+    INVERSE_S_BOX[byte as usize]
+}
+
+// the key schedule always uses the constant-time S-box, so that no backend leaks the key
+// through cache timing while the key is expanded
+fn sub_word(word: u32) -> u32 {
+    u32::from_be_bytes(word.to_be_bytes().map(constant_time::s_box))
+}
+
+// multiplication by x (i.e. 0x02) in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1
+const fn xtime(byte: u8) -> u8 {
+    (byte << 1) ^ if byte & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+const fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+fn add_round_key(block: Block, round_key: &Block) -> Block {
+    block.zip_map(round_key, |byte, round_key_byte| byte ^ round_key_byte)
+}
+
+fn sub_bytes(block: Block) -> Block {
+    block.map(s_box)
+}
+
+fn inv_sub_bytes(block: Block) -> Block {
+    block.map(inverse_s_box)
+}
+
+// row r is cyclically shifted left by r bytes
+fn shift_rows(block: Block) -> Block {
+    Block::from_fn(|row, column| block[(row, (column + row) % 4)])
+}
+
+fn inv_shift_rows(block: Block) -> Block {
+    Block::from_fn(|row, column| block[(row, (column + 4 - row) % 4)])
+}
+
+// multiply every column by the circulant matrix built from the given coefficients
+fn mix_columns_with(block: Block, coefficients: [u8; 4]) -> Block {
+    Block::from_fn(|row, column| {
+        (0..4).fold(0, |acc, i| {
+            acc ^ gf_mul(coefficients[(4 + i - row) % 4], block[(i, column)])
+        })
+    })
+}
+
+fn mix_columns(block: Block) -> Block {
+    mix_columns_with(block, [0x02, 0x03, 0x01, 0x01])
+}
+
+fn inv_mix_columns(block: Block) -> Block {
+    mix_columns_with(block, [0x0e, 0x0b, 0x0d, 0x09])
+}
+
+fn cipher(input: &[u8; 16], round_keys: &[Block]) -> [u8; 16] {
+    let round_count = round_keys.len() - 1;
+    let mut state = add_round_key(Block::from_column_slice(input), &round_keys[0]);
+    for round_key in &round_keys[1..round_count] {
+        state = add_round_key(mix_columns(shift_rows(sub_bytes(state))), round_key);
+    }
+    state = add_round_key(shift_rows(sub_bytes(state)), &round_keys[round_count]);
+    *arrayref::array_ref!(state.as_slice(), 0, 16)
+}
+
+fn inv_cipher(input: &[u8; 16], round_keys: &[Block]) -> [u8; 16] {
+    let round_count = round_keys.len() - 1;
+    let mut state = add_round_key(Block::from_column_slice(input), &round_keys[round_count]);
+    for round_key in round_keys[1..round_count].iter().rev() {
+        state = inv_mix_columns(add_round_key(
+            inv_sub_bytes(inv_shift_rows(state)),
+            round_key,
+        ));
+    }
+    state = add_round_key(inv_sub_bytes(inv_shift_rows(state)), &round_keys[0]);
+    *arrayref::array_ref!(state.as_slice(), 0, 16)
+}
+
+// which implementation of the cipher an Aes uses, all of them produce identical output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    // the FIPS-197 transformations applied one by one to a 4x4 byte matrix
+    Reference,
+    // 32 bit T-table lookups, several times faster than the reference
+    TTable,
+    // no secret dependent memory accesses or branches, immune to cache-timing attacks but slow
+    ConstantTime,
+}
+
+// an expanded AES key schedule, for 128, 192 or 256 bit keys
+#[derive(Clone, Debug)]
+pub struct Aes {
+    round_keys: Vec<Block>,
+    t_table_round_keys: t_table::RoundKeys,
+    backend: Backend,
+}
+
+impl Aes {
+    // a non-standard number of rounds, for experimenting with reduced-round AES
+    pub fn with_round_count(key: &[u8], round_count: usize) -> Result<Aes, String> {
+        match key.len() {
+            16 | 24 | 32 if round_count > 0 => {
+                let round_keys = key_expansion(key, round_count);
+                Ok(Aes {
+                    t_table_round_keys: t_table::RoundKeys::new(&round_keys),
+                    round_keys,
+                    backend: Backend::TTable,
+                })
+            }
+            16 | 24 | 32 => Err(String::from("AES needs at least one round")),
+            len => Err(format!("Invalid AES key length: {} bytes", len)),
+        }
+    }
+
+    pub fn with_backend(self, backend: Backend) -> Aes {
+        Aes { backend, ..self }
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    pub fn round_count(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+}
+
+impl BlockCipher for Aes {
+    // 10, 12 or 14 rounds depending on the key length, fails on any other key length
+    fn new(key: &[u8]) -> Result<Aes, String> {
+        Aes::with_round_count(key, key.len() / 4 + 6)
+    }
+
+    fn block_size(&self) -> usize {
+        BLOCK_SIZE
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let input = arrayref::array_ref!(block, 0, 16);
+        let ciphertext = match self.backend {
+            Backend::Reference => cipher(input, &self.round_keys),
+            Backend::TTable => t_table::cipher(input, &self.t_table_round_keys),
+            Backend::ConstantTime => constant_time::cipher(input, &self.round_keys),
+        };
+        block.copy_from_slice(&ciphertext);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let input = arrayref::array_ref!(block, 0, 16);
+        let plaintext = match self.backend {
+            Backend::Reference => inv_cipher(input, &self.round_keys),
+            Backend::TTable => t_table::inv_cipher(input, &self.t_table_round_keys),
+            Backend::ConstantTime => constant_time::inv_cipher(input, &self.round_keys),
+        };
+        block.copy_from_slice(&plaintext);
+    }
+}
+
+pub fn encrypt_block(plaintext: &[u8; 16], key: &[u8; 16]) -> [u8; 16] {
+    let mut block = *plaintext;
+    Aes::new(key).unwrap().encrypt_block(&mut block);
+    block
+}
+
+pub fn decrypt_block(ciphertext: &[u8; 16], key: &[u8; 16]) -> [u8; 16] {
+    let mut block = *ciphertext;
+    Aes::new(key).unwrap().decrypt_block(&mut block);
+    block
+}
+
+// decrypt every 16 byte block independently (ciphertext must be a multiple of the block size)
+pub fn aes_128_ecb(ciphertext: &[u8], key: &[u8; 16]) -> Vec<u8> {
+    block_cipher::ecb_decrypt(&Aes::new(key).unwrap(), ciphertext)
+}
@@ -0,0 +1,121 @@
+// AES on four 32 bit column words, with SubBytes, ShiftRows and MixColumns folded into table lookups
+use super::{gf_mul, inv_mix_columns, Block, INVERSE_S_BOX, S_BOX};
+
+type Tables = [[u32; 256]; 4];
+
+// table j maps a byte in row j of a column to its contribution to the mixed column
+const fn t_tables(s_box: &[u8; 256], coefficients: [u8; 4]) -> Tables {
+    let mut tables = [[0; 256]; 4];
+    let mut i = 0;
+    while i < 256 {
+        let s = s_box[i];
+        let word = u32::from_be_bytes([
+            gf_mul(coefficients[0], s),
+            gf_mul(coefficients[1], s),
+            gf_mul(coefficients[2], s),
+            gf_mul(coefficients[3], s),
+        ]);
+        let mut j = 0;
+        while j < 4 {
+            tables[j][i] = word.rotate_right(8 * j as u32);
+            j += 1;
+        }
+        i += 1;
+    }
+    tables
+}
+
+const ENCRYPTION_TABLES: Tables = t_tables(&S_BOX, [0x02, 0x01, 0x01, 0x03]);
+const DECRYPTION_TABLES: Tables = t_tables(&INVERSE_S_BOX, [0x0e, 0x09, 0x0d, 0x0b]);
+
+// round keys as column words, with InvMixColumns applied to the inner decryption round keys
+// so that decryption has the same structure as encryption (FIPS-197 section 5.3.5)
+#[derive(Clone, Debug)]
+pub(super) struct RoundKeys {
+    encryption: Vec<u32>,
+    decryption: Vec<u32>,
+}
+
+fn block_to_words(block: &Block) -> Vec<u32> {
+    block
+        .as_slice()
+        .chunks(4)
+        .map(|column| u32::from_be_bytes(*arrayref::array_ref!(column, 0, 4)))
+        .collect()
+}
+
+impl RoundKeys {
+    pub(super) fn new(round_keys: &[Block]) -> RoundKeys {
+        let round_count = round_keys.len() - 1;
+        let encryption = round_keys.iter().flat_map(block_to_words).collect();
+        let decryption = round_keys
+            .iter()
+            .rev()
+            .enumerate()
+            .flat_map(|(round, round_key)| match round {
+                0 => block_to_words(round_key),
+                round if round == round_count => block_to_words(round_key),
+                _ => block_to_words(&inv_mix_columns(*round_key)),
+            })
+            .collect();
+        RoundKeys {
+            encryption,
+            decryption,
+        }
+    }
+}
+
+fn byte(word: u32, row: usize) -> usize {
+    (word >> (24 - 8 * row) & 0xff) as usize
+}
+
+// column(c, row) is the input column that ends up in column c after (Inv)ShiftRows
+fn rounds(
+    input: &[u8; 16],
+    round_keys: &[u32],
+    tables: &Tables,
+    s_box: &[u8; 256],
+    column: fn(usize, usize) -> usize,
+) -> [u8; 16] {
+    let round_count = round_keys.len() / 4 - 1;
+    let mut state = [0; 4];
+    for c in 0..4 {
+        state[c] = u32::from_be_bytes(*arrayref::array_ref!(input, 4 * c, 4)) ^ round_keys[c];
+    }
+    for round_key in round_keys[4..4 * round_count].chunks(4) {
+        let previous = state;
+        for c in 0..4 {
+            state[c] = (0..4).fold(round_key[c], |acc, row| {
+                acc ^ tables[row][byte(previous[column(c, row)], row)]
+            });
+        }
+    }
+    let mut output = [0; 16];
+    for c in 0..4 {
+        for row in 0..4 {
+            output[4 * c + row] = s_box[byte(state[column(c, row)], row)]
+                ^ round_keys[4 * round_count + c].to_be_bytes()[row];
+        }
+    }
+    output
+}
+
+pub(super) fn cipher(input: &[u8; 16], round_keys: &RoundKeys) -> [u8; 16] {
+    rounds(
+        input,
+        &round_keys.encryption,
+        &ENCRYPTION_TABLES,
+        &S_BOX,
+        |c, row| (c + row) % 4,
+    )
+}
+
+pub(super) fn inv_cipher(input: &[u8; 16], round_keys: &RoundKeys) -> [u8; 16] {
+    rounds(
+        input,
+        &round_keys.decryption,
+        &DECRYPTION_TABLES,
+        &INVERSE_S_BOX,
+        |c, row| (c + 4 - row) % 4,
+    )
+}
@@ -0,0 +1,35 @@
+// a keyed permutation over fixed size blocks, the primitive every mode of operation is built on
+pub trait BlockCipher {
+    fn new(key: &[u8]) -> Result<Self, String>
+    where
+        Self: Sized;
+
+    // block size in bytes
+    fn block_size(&self) -> usize;
+
+    // encrypt a single block in place (block.len() must equal block_size())
+    fn encrypt_block(&self, block: &mut [u8]);
+
+    // decrypt a single block in place (block.len() must equal block_size())
+    fn decrypt_block(&self, block: &mut [u8]);
+}
+
+// encrypt every block independently (plaintext must be a multiple of the block size)
+pub fn ecb_encrypt<C: BlockCipher>(cipher: &C, plaintext: &[u8]) -> Vec<u8> {
+    assert_eq!(plaintext.len() % cipher.block_size(), 0);
+    let mut ciphertext = plaintext.to_vec();
+    for block in ciphertext.chunks_mut(cipher.block_size()) {
+        cipher.encrypt_block(block);
+    }
+    ciphertext
+}
+
+// decrypt every block independently (ciphertext must be a multiple of the block size)
+pub fn ecb_decrypt<C: BlockCipher>(cipher: &C, ciphertext: &[u8]) -> Vec<u8> {
+    assert_eq!(ciphertext.len() % cipher.block_size(), 0);
+    let mut plaintext = ciphertext.to_vec();
+    for block in plaintext.chunks_mut(cipher.block_size()) {
+        cipher.decrypt_block(block);
+    }
+    plaintext
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::LinkedList;
 use std::f64;
 use std::iter::FromIterator;
@@ -58,7 +59,7 @@ pub fn base64_decode(string: &str) -> Vec<u8> {
     let b64 = String::from("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/");
     let mut buf: [u8; 4] = [0; 4];
     let mut padding_count = 3;
-    let string_len = string.as_bytes().len();
+    let string_len = string.len();
     const MAX_PADDING_COUNT: usize = 3;
 
     for i in string_len - MAX_PADDING_COUNT..string_len {
@@ -71,13 +72,12 @@ pub fn base64_decode(string: &str) -> Vec<u8> {
 
     let real_string_len = string_len - padding_count;
     let mut result: Vec<u8> = vec![0; (real_string_len as f64 * 3.0 / 4.0) as usize];
-    for (quad, out_triplet) in string[0..real_string_len]
-        .as_bytes()
+    for (quad, out_triplet) in string.as_bytes()[0..real_string_len]
         .chunks(4)
         .zip(result.as_mut_slice().chunks_mut(3))
     {
-        for i in 0..buf.len() {
-            buf[i] = match quad.get(i) {
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = match quad.get(i) {
                 Some(x) => match b64.find(*x as char) {
                     Some(ind) => ind as u8,
                     None => 0,
@@ -106,8 +106,8 @@ pub fn base64_encode(bytes: &[u8]) -> String {
             ]
         }
 
-        for i in 0..buf.len() {
-            buf[i] = match v.get(i) {
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = match v.get(i) {
                 Some(x) => *x,
                 None => 0,
             };
@@ -116,18 +116,16 @@ pub fn base64_encode(bytes: &[u8]) -> String {
         vo.copy_from_slice(&triplet2quad(buf)[0..volen]);
     }
     let padding_length = (4 - result.len() % 4) % 4;
-    for _ in 0..padding_length {
-        result.push(64);
-    }
-    return String::from_utf8(result.iter().map(|x| b64[*x as usize]).collect()).unwrap();
+    result.extend(std::iter::repeat_n(64, padding_length));
+    String::from_utf8(result.iter().map(|x| b64[*x as usize]).collect()).unwrap()
 }
 
 // convert c to 12, can fail nicely
 pub fn hex_as_decimal(ch: char) -> Result<u8, String> {
     match ch {
-        '0'...'9' => Ok((ch as u8) - ('0' as u8)),
-        'a'...'f' => Ok(10 + (ch as u8) - ('a' as u8)),
-        'A'...'F' => Ok(10 + (ch as u8) - ('A' as u8)),
+        '0'..='9' => Ok((ch as u8) - b'0'),
+        'a'..='f' => Ok(10 + (ch as u8) - b'a'),
+        'A'..='F' => Ok(10 + (ch as u8) - b'A'),
         _ => Err(format!("Invalid hexadecimal character: {}", ch)),
     }
 }
@@ -144,7 +142,7 @@ pub fn decode_hex(buf: &str) -> Vec<u8> {
         }
         result.push_front(num);
     }
-    Vec::from_iter(result.into_iter())
+    Vec::from_iter(result)
 }
 
 // convert a byte vector from decode_hex to a hexadecimal string
@@ -182,14 +180,14 @@ pub fn fixed_xor(buf1: &[u8], buf2: &[u8]) -> Vec<u8> {
 }
 
 // xor every byte in the buffer with the single byte.
-pub fn single_byte_xor(buf: &Vec<u8>, byte: u8) -> Vec<u8> {
+pub fn single_byte_xor(buf: &[u8], byte: u8) -> Vec<u8> {
     buf.iter().map(|c| c ^ byte).collect()
 }
 
 pub fn char_frequency(buf: &[u8]) -> HashMap<char, f64> {
     let mut result = HashMap::new();
-    for i in 0..buf.len() {
-        *result.entry(buf[i] as char).or_insert(0.0) += 1.0;
+    for &byte in buf {
+        *result.entry(byte as char).or_insert(0.0) += 1.0;
     }
     for (_, val) in result.iter_mut() {
         // converting all values from count to frequency (in one go instead of per character per update)
@@ -202,9 +200,9 @@ pub fn decrypt_single_byte_xor_english(ciphertext: &[u8]) -> (Option<String>, u8
     let (mut min_chi_squared, mut min_key, mut decryption) =
         (similarity_to_english(ciphertext), 0, ciphertext.to_owned());
 
-    'outer: for i in 1..255 {
-        let attempted_decryption = single_byte_xor(&ciphertext.to_owned(), i);
-        'inner: for ch in &attempted_decryption {
+    'outer: for i in 1..=255 {
+        let attempted_decryption = single_byte_xor(ciphertext, i);
+        for ch in &attempted_decryption {
             if *ch > 127 {
                 continue 'outer;
             }
@@ -216,10 +214,7 @@ pub fn decrypt_single_byte_xor_english(ciphertext: &[u8]) -> (Option<String>, u8
             decryption = attempted_decryption;
         }
     }
-    let plaintext_option = match String::from_utf8(decryption) {
-        Ok(plaintext) => Some(plaintext),
-        Err(_) => None,
-    };
+    let plaintext_option = String::from_utf8(decryption).ok();
     (plaintext_option, min_key, min_chi_squared)
 }
 
@@ -448,147 +443,27 @@ pub fn break_vigenere(ciphertext: &[u8]) -> String {
         .map(|vec| String::from_utf8((*vec).clone()).unwrap())
         .collect();
 
-    let solution = string_parts.concat();
-
-    solution
+    string_parts.concat()
 }
 
-pub mod aes {
-    use nalgebra::Matrix4;
-    type Block = Matrix4<u8>;
-    const S_BOX: [u8; 256] = [
-        0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab,
-        0x76, 0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4,
-        0x72, 0xc0, 0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71,
-        0xd8, 0x31, 0x15, 0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2,
-        0xeb, 0x27, 0xb2, 0x75, 0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6,
-        0xb3, 0x29, 0xe3, 0x2f, 0x84, 0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb,
-        0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf, 0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45,
-        0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8, 0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5,
-        0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2, 0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44,
-        0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73, 0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a,
-        0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb, 0xe0, 0x32, 0x3a, 0x0a, 0x49,
-        0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79, 0xe7, 0xc8, 0x37, 0x6d,
-        0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08, 0xba, 0x78, 0x25,
-        0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a, 0x70, 0x3e,
-        0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e, 0xe1,
-        0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
-        0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb,
-        0x16,
-    ];
-    const INVERSE_S_BOX: [u8; 256] = [
-        0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3, 0xd7,
-        0xfb, 0x7c, 0xe3, 0x39, 0x82, 0x9b, 0x2f, 0xff, 0x87, 0x34, 0x8e, 0x43, 0x44, 0xc4, 0xde,
-        0xe9, 0xcb, 0x54, 0x7b, 0x94, 0x32, 0xa6, 0xc2, 0x23, 0x3d, 0xee, 0x4c, 0x95, 0x0b, 0x42,
-        0xfa, 0xc3, 0x4e, 0x08, 0x2e, 0xa1, 0x66, 0x28, 0xd9, 0x24, 0xb2, 0x76, 0x5b, 0xa2, 0x49,
-        0x6d, 0x8b, 0xd1, 0x25, 0x72, 0xf8, 0xf6, 0x64, 0x86, 0x68, 0x98, 0x16, 0xd4, 0xa4, 0x5c,
-        0xcc, 0x5d, 0x65, 0xb6, 0x92, 0x6c, 0x70, 0x48, 0x50, 0xfd, 0xed, 0xb9, 0xda, 0x5e, 0x15,
-        0x46, 0x57, 0xa7, 0x8d, 0x9d, 0x84, 0x90, 0xd8, 0xab, 0x00, 0x8c, 0xbc, 0xd3, 0x0a, 0xf7,
-        0xe4, 0x58, 0x05, 0xb8, 0xb3, 0x45, 0x06, 0xd0, 0x2c, 0x1e, 0x8f, 0xca, 0x3f, 0x0f, 0x02,
-        0xc1, 0xaf, 0xbd, 0x03, 0x01, 0x13, 0x8a, 0x6b, 0x3a, 0x91, 0x11, 0x41, 0x4f, 0x67, 0xdc,
-        0xea, 0x97, 0xf2, 0xcf, 0xce, 0xf0, 0xb4, 0xe6, 0x73, 0x96, 0xac, 0x74, 0x22, 0xe7, 0xad,
-        0x35, 0x85, 0xe2, 0xf9, 0x37, 0xe8, 0x1c, 0x75, 0xdf, 0x6e, 0x47, 0xf1, 0x1a, 0x71, 0x1d,
-        0x29, 0xc5, 0x89, 0x6f, 0xb7, 0x62, 0x0e, 0xaa, 0x18, 0xbe, 0x1b, 0xfc, 0x56, 0x3e, 0x4b,
-        0xc6, 0xd2, 0x79, 0x20, 0x9a, 0xdb, 0xc0, 0xfe, 0x78, 0xcd, 0x5a, 0xf4, 0x1f, 0xdd, 0xa8,
-        0x33, 0x88, 0x07, 0xc7, 0x31, 0xb1, 0x12, 0x10, 0x59, 0x27, 0x80, 0xec, 0x5f, 0x60, 0x51,
-        0x7f, 0xa9, 0x19, 0xb5, 0x4a, 0x0d, 0x2d, 0xe5, 0x7a, 0x9f, 0x93, 0xc9, 0x9c, 0xef, 0xa0,
-        0xe0, 0x3b, 0x4d, 0xae, 0x2a, 0xf5, 0xb0, 0xc8, 0xeb, 0xbb, 0x3c, 0x83, 0x53, 0x99, 0x61,
-        0x17, 0x2b, 0x04, 0x7e, 0xba, 0x77, 0xd6, 0x26, 0xe1, 0x69, 0x14, 0x63, 0x55, 0x21, 0x0c,
-        0x7d,
-    ];
-
-    pub fn round_constants(round_count: u8) -> Vec<u32> {
-        (0..round_count).map(round_constant_i).collect()
-    }
-
-    fn round_constant_i(i: u8) -> u32 {
-        fn round_constant_i_top_byte(i: u8) -> u8 {
-            match i {
-                0 => 1,
-                _ if round_constant_i_top_byte(i - 1) < 0x80 => {
-                    round_constant_i_top_byte(i - 1).wrapping_mul(2)
-                }
-                _ => ((round_constant_i_top_byte(i - 1) as u16).wrapping_mul(2) ^ 0x11b) as u8,
-            }
-        }
-        (round_constant_i_top_byte(i) as u32) << 24
-    }
-
-    fn key_expansion(key: &[u8; 16], round_count: u8) -> Vec<Vec<u8>> {
-        const N: usize = 4;
-        let round_constants = round_constants(round_count);
-        let mut round_keys: Vec<Vec<u8>> = Vec::new();
-        let mut round_keys_u32: Vec<u32> = Vec::with_capacity((N as u8 * round_count) as usize);
-        let mut key_in_32bit_words: Vec<u32> = Vec::with_capacity(4);
-        for i in (0..key.len()).step_by(4) {
-            key_in_32bit_words.push(u32::from_be_bytes(*arrayref::array_ref!(
-                key[i..i + 4],
-                0,
-                4
-            )));
-        }
-        for i in 0..round_keys_u32.len() {
-            round_keys_u32.push(match i {
-                i if i < N => key_in_32bit_words[i],
-                i if i >= N && (i % N == 0) => {
-                    round_keys_u32[i - N]
-                        ^ sub_word(round_keys_u32[i - 1]).rotate_left(1)
-                        ^ round_constants[i / N]
-                }
-                i if i >= N && N > 6 && (i % N == 4) => {
-                    round_keys_u32[i - N] ^ sub_word(round_keys_u32[i - 1])
-                }
-                _ => round_keys_u32[i - N] ^ round_keys_u32[i - 1],
-            });
-        }
-        for i in 0..round_keys_u32.len() {}
-        round_keys
-    }
-    fn s_box(byte: u8) -> u8 {
-        S_BOX[byte as usize]
-    }
-
-    fn sub_word(word: u32) -> u32 {
-        let subbed_bytes_vec = word
-            .to_be_bytes()
-            .iter()
-            .map(|x| s_box(*x))
-            .collect::<Vec<u8>>();
-        let mut subbed_bytes = Vec::with_capacity(4);
-        for i in 0..4 {
-            subbed_bytes[i] = subbed_bytes_vec[i];
-        }
-
-        u32::from_be_bytes(*arrayref::array_ref!(subbed_bytes, 0, 4))
-    }
-
-    fn add_round_key(block: Block, round_key: Block) -> Block {
-        block.map(|byte| byte ^ round_key_byte)
-    }
-
-    fn sub_bytes(block: Block) -> Block {
-        block.map(s_box)
-    }
-
-    fn shift_row(block: &mut Block, row_number: usize, shift_amount: usize) -> &mut Block {
-        let mut row = block.row_mut(row_number);
-        for i in 0..row.len() {
-            row[i] = row[(i + shift_amount) % row.len()];
-        }
-        block
-    }
-    fn shift_rows(mut block: &mut Block) -> &mut Block {
-        for i in 1..3 {
-            block = shift_row(block, i, i);
-        }
-        block
-    }
-
-    fn mix_columns(mut block: &mut Block) -> &mut Block {
-        block
-    }
-    pub fn aes_128_ecb(ciphertext: &[u8], key: &[u8; 16]) -> Vec<u8> {
-        return vec![];
-    }
+// number of blocks that are a repeat of an earlier block, ECB leaks this while other modes don't
+pub fn count_repeated_blocks(ciphertext: &[u8], block_size: usize) -> usize {
+    let blocks: Vec<&[u8]> = ciphertext.chunks_exact(block_size).collect();
+    let unique_blocks: HashSet<&[u8]> = blocks.iter().cloned().collect();
+    blocks.len() - unique_blocks.len()
+}
 
+// rank hex encoded ciphertexts by how many repeated 16 byte blocks they contain, most repeats first.
+// returns (index into hex_ciphertexts, repeated block count) pairs
+pub fn rank_ecb_candidates(hex_ciphertexts: &[&str]) -> Vec<(usize, usize)> {
+    let mut ranking: Vec<(usize, usize)> = hex_ciphertexts
+        .iter()
+        .map(|hex_ciphertext| count_repeated_blocks(&decode_hex(hex_ciphertext), 16))
+        .enumerate()
+        .collect();
+    ranking.sort_by(|(_, repeats1), (_, repeats2)| repeats2.cmp(repeats1));
+    ranking
 }
+
+pub mod aes;
+pub mod block_cipher;
@@ -0,0 +1,134 @@
+use super::pkcs7;
+use crate::set1;
+use crate::set1::aes::Aes;
+use crate::set1::block_cipher::{self, BlockCipher};
+use rand::Rng;
+
+const CHALLENGE_12_SUFFIX: &str = "Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkgaGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBqdXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUgYnkK";
+
+// ECB(prefix || input || suffix) under a fixed key, prefix and suffix being the secrets
+pub struct EcbOracle<C: BlockCipher> {
+    cipher: C,
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+}
+
+impl<C: BlockCipher> EcbOracle<C> {
+    pub fn new(cipher: C, prefix: &[u8], suffix: &[u8]) -> EcbOracle<C> {
+        EcbOracle {
+            cipher,
+            prefix: prefix.to_vec(),
+            suffix: suffix.to_vec(),
+        }
+    }
+
+    pub fn encrypt(&self, input: &[u8]) -> Vec<u8> {
+        let plaintext = [&self.prefix[..], input, &self.suffix[..]].concat();
+        let padded = pkcs7::pad(&plaintext, self.cipher.block_size());
+        block_cipher::ecb_encrypt(&self.cipher, &padded)
+    }
+}
+
+impl EcbOracle<Aes> {
+    // random key, no prefix and the challenge's secret suffix
+    pub fn challenge12() -> EcbOracle<Aes> {
+        let aes = Aes::new(&super::random_aes_key()).unwrap();
+        EcbOracle::new(aes, &[], &set1::base64_decode(CHALLENGE_12_SUFFIX))
+    }
+
+    // like challenge 12, plus a random count of random bytes before the attacker's input
+    pub fn challenge14() -> EcbOracle<Aes> {
+        let aes = Aes::new(&super::random_aes_key()).unwrap();
+        let prefix = super::random_bytes(rand::thread_rng().gen_range(0..=64));
+        EcbOracle::new(aes, &prefix, &set1::base64_decode(CHALLENGE_12_SUFFIX))
+    }
+}
+
+fn block(ciphertext: &[u8], block_size: usize, index: usize) -> &[u8] {
+    &ciphertext[index * block_size..(index + 1) * block_size]
+}
+
+// grow the input until the ciphertext grows by a block. returns the block size and the combined
+// length of whatever the oracle adds around the input
+pub fn discover_block_size<F: Fn(&[u8]) -> Vec<u8>>(oracle: F) -> Result<(usize, usize), String> {
+    const MAX_BLOCK_SIZE: usize = 256;
+    let initial_length = oracle(&[]).len();
+    for input_length in 1..=MAX_BLOCK_SIZE {
+        let length = oracle(&vec![0; input_length]).len();
+        if length > initial_length {
+            // the ciphertext grows once the input fills the padding block completely
+            return Ok((length - initial_length, initial_length - input_length));
+        }
+    }
+    Err(format!(
+        "Ciphertext did not grow with up to {} bytes of input",
+        MAX_BLOCK_SIZE
+    ))
+}
+
+// three identical blocks of input always contain two aligned identical blocks
+pub fn is_ecb<F: Fn(&[u8]) -> Vec<u8>>(oracle: F, block_size: usize) -> bool {
+    set1::count_repeated_blocks(&oracle(&vec![0; 3 * block_size]), block_size) > 0
+}
+
+// find the shortest filler that aligns two blocks of our own input, using two different input
+// bytes so that neither secret bytes equal to the input nor repeated secret blocks confuse us
+pub fn discover_prefix_length<F: Fn(&[u8]) -> Vec<u8>>(
+    oracle: F,
+    block_size: usize,
+) -> Result<usize, String> {
+    for filler_length in 0..block_size {
+        let ciphertexts: Vec<Vec<u8>> = [1, 2]
+            .iter()
+            .map(|&byte| {
+                let mut input = vec![0; filler_length];
+                input.extend(vec![byte; 2 * block_size]);
+                oracle(&input)
+            })
+            .collect();
+        let block_count = ciphertexts[0].len() / block_size;
+        let aligned_block = (0..block_count - 1).find(|&index| {
+            ciphertexts.iter().all(|ciphertext| {
+                block(ciphertext, block_size, index) == block(ciphertext, block_size, index + 1)
+            }) && block(&ciphertexts[0], block_size, index)
+                != block(&ciphertexts[1], block_size, index)
+        });
+        if let Some(index) = aligned_block {
+            return Ok(index * block_size - filler_length);
+        }
+    }
+    Err(String::from("Could not align the input to a block"))
+}
+
+// recover the secret suffix one byte at a time: shift the next unknown byte to the end of a block
+// whose other bytes we know, then try all 256 values for it
+pub fn break_ecb<F: Fn(&[u8]) -> Vec<u8>>(oracle: F) -> Result<Vec<u8>, String> {
+    let (block_size, secret_length) = discover_block_size(&oracle)?;
+    if !is_ecb(&oracle, block_size) {
+        return Err(String::from("Oracle does not encrypt with ECB"));
+    }
+    let prefix_length = discover_prefix_length(&oracle, block_size)?;
+    let suffix_length = secret_length - prefix_length;
+    // filler that completes the prefix's last block, and the index of the first block after it
+    let alignment_length = (block_size - prefix_length % block_size) % block_size;
+    let first_block = (prefix_length + alignment_length) / block_size;
+
+    let mut known = vec![0; block_size - 1];
+    for i in 0..suffix_length {
+        let shift = block_size - 1 - i % block_size;
+        let ciphertext = oracle(&vec![0; alignment_length + shift]);
+        let target = block(&ciphertext, block_size, first_block + i / block_size);
+
+        let mut input = vec![0; alignment_length];
+        input.extend_from_slice(&known[known.len() - (block_size - 1)..]);
+        input.push(0);
+        let byte = (0..=255)
+            .find(|&candidate| {
+                *input.last_mut().unwrap() = candidate;
+                block(&oracle(&input), block_size, first_block) == target
+            })
+            .ok_or_else(|| format!("No byte value matches suffix byte {}", i))?;
+        known.push(byte);
+    }
+    Ok(known.split_off(block_size - 1))
+}
@@ -0,0 +1,128 @@
+use super::pkcs7::{self, PaddingError};
+use crate::set1;
+use crate::set1::block_cipher::BlockCipher;
+
+// streaming CBC encryption, accepts plaintext in chunks of any size and pads it on finalize
+pub struct Encryptor<'a, C: BlockCipher> {
+    cipher: &'a C,
+    previous_block: Vec<u8>,
+    buffer: Vec<u8>,
+}
+
+impl<'a, C: BlockCipher> Encryptor<'a, C> {
+    pub fn new(cipher: &'a C, iv: &[u8]) -> Encryptor<'a, C> {
+        assert_eq!(iv.len(), cipher.block_size());
+        Encryptor {
+            cipher,
+            previous_block: iv.to_vec(),
+            buffer: Vec::new(),
+        }
+    }
+
+    // returns the ciphertext of every block completed by this chunk
+    pub fn update(&mut self, plaintext: &[u8]) -> Vec<u8> {
+        let block_size = self.cipher.block_size();
+        self.buffer.extend_from_slice(plaintext);
+        let complete_length = self.buffer.len() - self.buffer.len() % block_size;
+        let complete_blocks: Vec<u8> = self.buffer.drain(..complete_length).collect();
+        let mut ciphertext = Vec::with_capacity(complete_length);
+        for block in complete_blocks.chunks(block_size) {
+            let mut encrypted_block = set1::fixed_xor(block, &self.previous_block);
+            self.cipher.encrypt_block(&mut encrypted_block);
+            ciphertext.extend_from_slice(&encrypted_block);
+            self.previous_block = encrypted_block;
+        }
+        ciphertext
+    }
+
+    // pads whatever is left over and returns the last one or two ciphertext blocks
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padded = pkcs7::pad(&self.buffer, self.cipher.block_size());
+        self.buffer.clear();
+        self.update(&padded)
+    }
+}
+
+// streaming CBC decryption, the last block is held back until finalize so that it can be unpadded
+pub struct Decryptor<'a, C: BlockCipher> {
+    cipher: &'a C,
+    previous_block: Vec<u8>,
+    buffer: Vec<u8>,
+}
+
+impl<'a, C: BlockCipher> Decryptor<'a, C> {
+    pub fn new(cipher: &'a C, iv: &[u8]) -> Decryptor<'a, C> {
+        assert_eq!(iv.len(), cipher.block_size());
+        Decryptor {
+            cipher,
+            previous_block: iv.to_vec(),
+            buffer: Vec::new(),
+        }
+    }
+
+    fn decrypt_blocks(&mut self, ciphertext: &[u8]) -> Vec<u8> {
+        let mut plaintext = Vec::with_capacity(ciphertext.len());
+        for block in ciphertext.chunks(self.cipher.block_size()) {
+            let mut decrypted_block = block.to_vec();
+            self.cipher.decrypt_block(&mut decrypted_block);
+            plaintext.extend(set1::fixed_xor(&decrypted_block, &self.previous_block));
+            self.previous_block = block.to_vec();
+        }
+        plaintext
+    }
+
+    // returns the plaintext of every block that is known not to be the last one
+    pub fn update(&mut self, ciphertext: &[u8]) -> Vec<u8> {
+        let block_size = self.cipher.block_size();
+        self.buffer.extend_from_slice(ciphertext);
+        if self.buffer.len() <= block_size {
+            return Vec::new();
+        }
+        let mut complete_length = self.buffer.len() - self.buffer.len() % block_size;
+        if complete_length == self.buffer.len() {
+            complete_length -= block_size;
+        }
+        let complete_blocks: Vec<u8> = self.buffer.drain(..complete_length).collect();
+        self.decrypt_blocks(&complete_blocks)
+    }
+
+    // decrypts and unpads the final block
+    pub fn finalize(mut self) -> Result<Vec<u8>, PaddingError> {
+        let block_size = self.cipher.block_size();
+        if self.buffer.len() != block_size {
+            return Err(PaddingError::BadLength);
+        }
+        let last_block = std::mem::take(&mut self.buffer);
+        pkcs7::unpad(&self.decrypt_blocks(&last_block), block_size)
+    }
+}
+
+pub fn encrypt<C: BlockCipher>(cipher: &C, iv: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    let mut encryptor = Encryptor::new(cipher, iv);
+    let mut ciphertext = encryptor.update(plaintext);
+    ciphertext.extend(encryptor.finalize());
+    ciphertext
+}
+
+pub fn decrypt<C: BlockCipher>(
+    cipher: &C,
+    iv: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, PaddingError> {
+    let mut decryptor = Decryptor::new(cipher, iv);
+    let mut plaintext = decryptor.update(ciphertext);
+    plaintext.extend(decryptor.finalize()?);
+    Ok(plaintext)
+}
+
+// CBC without padding (plaintext must be a multiple of the block size)
+pub fn encrypt_blocks<C: BlockCipher>(cipher: &C, iv: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    assert!(plaintext.len().is_multiple_of(cipher.block_size()));
+    Encryptor::new(cipher, iv).update(plaintext)
+}
+
+// CBC without unpadding (ciphertext must be a multiple of the block size)
+pub fn decrypt_blocks<C: BlockCipher>(cipher: &C, iv: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    assert!(ciphertext.len().is_multiple_of(cipher.block_size()));
+    Decryptor::new(cipher, iv).decrypt_blocks(ciphertext)
+}
@@ -0,0 +1,74 @@
+use super::cbc;
+use super::pkcs7::PaddingError;
+use super::userdata::{BitFlipForgery, UserdataCipher, UserdataService, ADMIN_TOKEN, PREFIX};
+use crate::set1;
+use crate::set1::aes::Aes;
+use crate::set1::block_cipher::BlockCipher;
+
+// AES-CBC under a random key and IV
+pub struct CbcCipher {
+    aes: Aes,
+    iv: Vec<u8>,
+}
+
+impl CbcCipher {
+    pub fn new() -> CbcCipher {
+        CbcCipher {
+            aes: Aes::new(&super::random_aes_key()).unwrap(),
+            iv: super::random_bytes(16),
+        }
+    }
+}
+
+impl Default for CbcCipher {
+    fn default() -> CbcCipher {
+        CbcCipher::new()
+    }
+}
+
+impl UserdataCipher for CbcCipher {
+    type Error = PaddingError;
+
+    fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        cbc::encrypt(&self.aes, &self.iv, plaintext)
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, PaddingError> {
+        cbc::decrypt(&self.aes, &self.iv, ciphertext)
+    }
+}
+
+// challenge 16's service encrypts under AES-CBC
+impl UserdataService<CbcCipher> {
+    pub fn new() -> UserdataService<CbcCipher> {
+        UserdataService::with_cipher(CbcCipher::new())
+    }
+}
+
+impl Default for UserdataService<CbcCipher> {
+    fn default() -> UserdataService<CbcCipher> {
+        UserdataService::new()
+    }
+}
+
+// send a block of filler followed by a placeholder as long as the token, then xor
+// placeholder ^ token into the filler's ciphertext block: that flips the same bits of the
+// placeholder's plaintext, and scrambles the filler block's plaintext
+pub fn forge_admin<F: Fn(&str) -> Vec<u8>>(encrypt_userdata: F) -> BitFlipForgery {
+    const BLOCK_SIZE: usize = 16;
+    let alignment_length = (BLOCK_SIZE - PREFIX.len() % BLOCK_SIZE) % BLOCK_SIZE;
+    let filler_start = PREFIX.len() + alignment_length;
+    let placeholder = "A".repeat(ADMIN_TOKEN.len());
+    let userdata = "A".repeat(alignment_length + BLOCK_SIZE) + &placeholder;
+
+    let mut ciphertext = encrypt_userdata(&userdata);
+    let flip_range = filler_start..filler_start + ADMIN_TOKEN.len();
+    let mask = set1::fixed_xor(placeholder.as_bytes(), ADMIN_TOKEN.as_bytes());
+    let flipped = set1::fixed_xor(&ciphertext[flip_range.clone()], &mask);
+    ciphertext[flip_range].copy_from_slice(&flipped);
+
+    BitFlipForgery {
+        ciphertext,
+        scrambled: filler_start..filler_start + BLOCK_SIZE,
+    }
+}
@@ -0,0 +1,113 @@
+use super::pkcs7;
+use crate::set1::aes::Aes;
+use crate::set1::block_cipher::{self, BlockCipher};
+
+// key/value pairs in the order they appear in the encoded cookie
+pub type Cookie = Vec<(String, String)>;
+
+// parse "foo=bar&baz=qux" into [("foo", "bar"), ("baz", "qux")]
+pub fn parse_cookie(encoded: &str) -> Result<Cookie, String> {
+    if encoded.is_empty() {
+        return Ok(Vec::new());
+    }
+    encoded
+        .split('&')
+        .map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) if !value.contains('=') => {
+                    Ok((String::from(key), String::from(value)))
+                }
+                _ => Err(format!("Invalid key=value pair: {}", pair)),
+            }
+        })
+        .collect()
+}
+
+// the metacharacters are removed from keys and values, so they can't inject extra pairs
+pub fn encode_cookie(cookie: &[(String, String)]) -> String {
+    fn sanitize(string: &str) -> String {
+        string
+            .chars()
+            .filter(|&ch| ch != '&' && ch != '=')
+            .collect()
+    }
+    cookie
+        .iter()
+        .map(|(key, value)| format!("{}={}", sanitize(key), sanitize(value)))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+pub fn profile_for(email: &str) -> String {
+    encode_cookie(&[
+        (String::from("email"), String::from(email)),
+        (String::from("uid"), String::from("10")),
+        (String::from("role"), String::from("user")),
+    ])
+}
+
+// hands out AES-ECB encrypted user profiles and trusts whatever role it decrypts
+pub struct ProfileService {
+    aes: Aes,
+}
+
+impl ProfileService {
+    pub fn new() -> ProfileService {
+        ProfileService {
+            aes: Aes::new(&super::random_aes_key()).unwrap(),
+        }
+    }
+
+    pub fn encrypted_profile_for(&self, email: &str) -> Vec<u8> {
+        let padded = pkcs7::pad(profile_for(email).as_bytes(), self.aes.block_size());
+        block_cipher::ecb_encrypt(&self.aes, &padded)
+    }
+
+    pub fn decrypt_profile(&self, ciphertext: &[u8]) -> Result<Cookie, String> {
+        if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(self.aes.block_size()) {
+            return Err(String::from("Invalid ciphertext length"));
+        }
+        let padded = block_cipher::ecb_decrypt(&self.aes, ciphertext);
+        let plaintext =
+            pkcs7::unpad(&padded, self.aes.block_size()).map_err(|error| error.to_string())?;
+        let encoded = String::from_utf8(plaintext).map_err(|error| error.to_string())?;
+        parse_cookie(&encoded)
+    }
+
+    pub fn is_admin(&self, ciphertext: &[u8]) -> bool {
+        match self.decrypt_profile(ciphertext) {
+            Ok(cookie) => cookie
+                .iter()
+                .any(|(key, value)| key == "role" && value == "admin"),
+            Err(_) => false,
+        }
+    }
+}
+
+impl Default for ProfileService {
+    fn default() -> ProfileService {
+        ProfileService::new()
+    }
+}
+
+// cut and paste two ciphertexts: one where "role=" ends a block, and one where "admin" plus its
+// PKCS#7 padding fills a whole block of its own
+pub fn forge_admin_profile<F: Fn(&str) -> Vec<u8>>(profile_oracle: F) -> Vec<u8> {
+    const BLOCK_SIZE: usize = 16;
+    let email_offset = "email=".len();
+    let role_offset = profile_for("").len() - "user".len();
+
+    let alignment = "A".repeat(BLOCK_SIZE - email_offset);
+    let admin_block = String::from_utf8(pkcs7::pad(b"admin", BLOCK_SIZE)).unwrap();
+    let admin_ciphertext = profile_oracle(&(alignment + &admin_block));
+
+    let email_length = (BLOCK_SIZE - role_offset % BLOCK_SIZE) % BLOCK_SIZE + BLOCK_SIZE;
+    let email = format!("{}@bar.com", "f".repeat(email_length - "@bar.com".len()));
+    let role_ciphertext = profile_oracle(&email);
+    let role_end = role_offset + email_length;
+
+    let mut forged = role_ciphertext[..role_end].to_vec();
+    forged.extend_from_slice(&admin_ciphertext[BLOCK_SIZE..2 * BLOCK_SIZE]);
+    forged
+}
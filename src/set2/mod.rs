@@ -0,0 +1,53 @@
+use crate::set1;
+use crate::set1::aes::Aes;
+use crate::set1::block_cipher::{self, BlockCipher};
+use rand::Rng;
+
+pub mod byte_at_a_time;
+pub mod cbc;
+pub mod cbc_bit_flipping;
+pub mod ecb_cut_and_paste;
+pub mod pkcs7;
+pub mod userdata;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Ecb,
+    Cbc,
+}
+
+pub fn random_bytes(count: usize) -> Vec<u8> {
+    let mut rng = rand::thread_rng();
+    (0..count).map(|_| rng.gen()).collect()
+}
+
+pub fn random_aes_key() -> [u8; 16] {
+    rand::thread_rng().gen()
+}
+
+// encrypt under a random key with ECB or CBC (chosen at random), after surrounding the input with
+// 5-10 random bytes on each side. returns the mode that was chosen so that guesses can be checked
+pub fn encryption_oracle(input: &[u8]) -> (Vec<u8>, Mode) {
+    let mut rng = rand::thread_rng();
+    let aes = Aes::new(&random_aes_key()).unwrap();
+    let mut plaintext = random_bytes(rng.gen_range(5..=10));
+    plaintext.extend_from_slice(input);
+    plaintext.extend(random_bytes(rng.gen_range(5..=10)));
+
+    if rng.gen() {
+        let padded = pkcs7::pad(&plaintext, aes.block_size());
+        (block_cipher::ecb_encrypt(&aes, &padded), Mode::Ecb)
+    } else {
+        let iv = random_bytes(aes.block_size());
+        (cbc::encrypt(&aes, &iv, &plaintext), Mode::Cbc)
+    }
+}
+
+// only reliable if the plaintext contained at least two aligned, identical blocks (e.g. 43 equal bytes
+// for a 16 byte block size and up to 10 bytes of prefix)
+pub fn detect_mode(ciphertext: &[u8]) -> Mode {
+    match set1::count_repeated_blocks(ciphertext, 16) {
+        0 => Mode::Cbc,
+        _ => Mode::Ecb,
+    }
+}
@@ -0,0 +1,52 @@
+use std::error::Error;
+use std::fmt;
+
+// the distinct ways a PKCS#7 padded buffer can be malformed, padding oracles tell these apart
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaddingError {
+    // the data is empty, not a whole number of blocks, or the pad byte is larger than a block
+    BadLength,
+    // the last byte is 0, which never occurs in valid padding
+    ZeroPadByte,
+    // the last n bytes are not all equal to n
+    InconsistentPadBytes,
+}
+
+impl fmt::Display for PaddingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaddingError::BadLength => write!(f, "Invalid PKCS#7 padded length"),
+            PaddingError::ZeroPadByte => write!(f, "Invalid PKCS#7 pad byte: 0"),
+            PaddingError::InconsistentPadBytes => write!(f, "Inconsistent PKCS#7 pad bytes"),
+        }
+    }
+}
+
+impl Error for PaddingError {}
+
+// pad to a multiple of block_size, always adding between 1 and block_size bytes
+pub fn pad(data: &[u8], block_size: usize) -> Vec<u8> {
+    assert!(block_size > 0 && block_size < 256);
+    let padding_length = block_size - data.len() % block_size;
+    let mut padded = data.to_vec();
+    padded.resize(data.len() + padding_length, padding_length as u8);
+    padded
+}
+
+pub fn unpad(data: &[u8], block_size: usize) -> Result<Vec<u8>, PaddingError> {
+    if data.is_empty() || !data.len().is_multiple_of(block_size) {
+        return Err(PaddingError::BadLength);
+    }
+    let padding_length = data[data.len() - 1] as usize;
+    if padding_length == 0 {
+        return Err(PaddingError::ZeroPadByte);
+    }
+    if padding_length > block_size {
+        return Err(PaddingError::BadLength);
+    }
+    let (unpadded, padding) = data.split_at(data.len() - padding_length);
+    if padding.iter().any(|&byte| byte as usize != padding_length) {
+        return Err(PaddingError::InconsistentPadBytes);
+    }
+    Ok(unpadded.to_vec())
+}
@@ -0,0 +1,56 @@
+// the userdata service shared by the bit flipping challenges (16, 26 and 27), independent of
+// the mode of operation it encrypts with
+use std::ops::Range;
+
+pub const PREFIX: &str = "comment1=cooking%20MCs;userdata=";
+pub const SUFFIX: &str = ";comment2=%20like%20a%20pound%20of%20bacon";
+pub const ADMIN_TOKEN: &str = ";admin=true;";
+
+// escape the metacharacters so userdata can't contain ";admin=true;" itself
+pub fn quote(userdata: &str) -> String {
+    userdata.replace(';', "%3B").replace('=', "%3D")
+}
+
+// a mode of operation under a secret key, for UserdataService to encrypt with
+pub trait UserdataCipher {
+    type Error;
+
+    fn encrypt(&self, plaintext: &[u8]) -> Vec<u8>;
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Self::Error>;
+}
+
+// encrypts quoted userdata between a fixed prefix and suffix
+pub struct UserdataService<C: UserdataCipher> {
+    cipher: C,
+}
+
+impl<C: UserdataCipher> UserdataService<C> {
+    pub fn with_cipher(cipher: C) -> UserdataService<C> {
+        UserdataService { cipher }
+    }
+
+    pub fn encrypt_userdata(&self, userdata: &str) -> Vec<u8> {
+        let plaintext = format!("{}{}{}", PREFIX, quote(userdata), SUFFIX);
+        self.cipher.encrypt(plaintext.as_bytes())
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, C::Error> {
+        self.cipher.decrypt(ciphertext)
+    }
+
+    // the plaintext isn't required to be valid UTF-8, so a scrambled block doesn't stop the check
+    pub fn is_admin(&self, ciphertext: &[u8]) -> bool {
+        match self.decrypt(ciphertext) {
+            Ok(plaintext) => plaintext
+                .windows(ADMIN_TOKEN.len())
+                .any(|window| window == ADMIN_TOKEN.as_bytes()),
+            Err(_) => false,
+        }
+    }
+}
+
+// a modified ciphertext, and the plaintext bytes its modification turned into garbage
+pub struct BitFlipForgery {
+    pub ciphertext: Vec<u8>,
+    pub scrambled: Range<usize>,
+}
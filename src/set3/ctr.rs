@@ -0,0 +1,160 @@
+use crate::set1::block_cipher::BlockCipher;
+
+// how the nonce and the block counter are laid out in the 16 byte counter block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CounterLayout {
+    // 64 bit nonce, then a 64 bit little-endian block counter (the Cryptopals format)
+    LittleEndian64,
+    // 96 bit nonce, then a 32 bit big-endian block counter (NIST SP 800-38A / GCM)
+    BigEndian96,
+}
+
+impl CounterLayout {
+    pub fn nonce_length(self) -> usize {
+        match self {
+            CounterLayout::LittleEndian64 => 8,
+            CounterLayout::BigEndian96 => 12,
+        }
+    }
+
+    // the last counter value before the counter would wrap around and repeat the keystream
+    pub fn max_counter(self) -> u64 {
+        match self {
+            CounterLayout::LittleEndian64 => u64::MAX,
+            CounterLayout::BigEndian96 => u32::MAX as u64,
+        }
+    }
+}
+
+// the CTR keystream as an iterator of bytes, which can be moved to any byte offset; it ends
+// when the block counter runs out rather than wrapping around
+#[derive(Clone)]
+pub struct Keystream<C: BlockCipher> {
+    cipher: C,
+    nonce: Vec<u8>,
+    layout: CounterLayout,
+    initial_counter: u64,
+    position: u64,
+    // the keystream block containing position, and its index
+    block: Option<(u64, Vec<u8>)>,
+}
+
+impl<C: BlockCipher> Keystream<C> {
+    pub fn new(cipher: C, nonce: &[u8], layout: CounterLayout) -> Result<Keystream<C>, String> {
+        if cipher.block_size() != 16 {
+            return Err(format!(
+                "CTR needs a 16 byte block cipher, not {} bytes",
+                cipher.block_size()
+            ));
+        }
+        if nonce.len() != layout.nonce_length() {
+            return Err(format!(
+                "Invalid nonce length for {:?}: {} bytes",
+                layout,
+                nonce.len()
+            ));
+        }
+        Ok(Keystream {
+            cipher,
+            nonce: nonce.to_vec(),
+            layout,
+            initial_counter: 0,
+            position: 0,
+            block: None,
+        })
+    }
+
+    // the counter of the first block, 0 by default
+    pub fn with_initial_counter(self, initial_counter: u64) -> Result<Keystream<C>, String> {
+        if initial_counter > self.layout.max_counter() {
+            return Err(format!(
+                "Initial counter {} doesn't fit in the {:?} counter",
+                initial_counter, self.layout
+            ));
+        }
+        Ok(Keystream {
+            initial_counter,
+            block: None,
+            ..self
+        })
+    }
+
+    // None once the counter has run out
+    fn counter(&self, block_index: u64) -> Option<u64> {
+        self.initial_counter
+            .checked_add(block_index)
+            .filter(|&counter| counter <= self.layout.max_counter())
+    }
+
+    pub fn counter_block(&self, block_index: u64) -> Option<Vec<u8>> {
+        let counter = self.counter(block_index)?;
+        let mut counter_block = self.nonce.clone();
+        match self.layout {
+            CounterLayout::LittleEndian64 => counter_block.extend(&counter.to_le_bytes()),
+            CounterLayout::BigEndian96 => counter_block.extend(&(counter as u32).to_be_bytes()),
+        }
+        Some(counter_block)
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn seek(&mut self, position: u64) {
+        self.position = position;
+    }
+
+    // xor the keystream into data, starting at the current position; data is left alone if the
+    // counter would run out before its end
+    pub fn apply_keystream(&mut self, data: &mut [u8]) -> Result<(), String> {
+        if !data.is_empty() {
+            let last_block = self
+                .position
+                .checked_add(data.len() as u64 - 1)
+                .map(|last| last / 16);
+            if last_block.and_then(|index| self.counter(index)).is_none() {
+                return Err(format!(
+                    "The {:?} counter runs out before the end of the data",
+                    self.layout
+                ));
+            }
+        }
+        for (byte, keystream_byte) in data.iter_mut().zip(self) {
+            *byte ^= keystream_byte;
+        }
+        Ok(())
+    }
+}
+
+impl<C: BlockCipher> Iterator for Keystream<C> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let block_index = self.position / 16;
+        let offset = (self.position % 16) as usize;
+        let byte = match &self.block {
+            Some((index, block)) if *index == block_index => block[offset],
+            _ => {
+                let mut block = self.counter_block(block_index)?;
+                self.cipher.encrypt_block(&mut block);
+                let byte = block[offset];
+                self.block = Some((block_index, block));
+                byte
+            }
+        };
+        self.position += 1;
+        Some(byte)
+    }
+}
+
+// CTR encryption and decryption are the same operation
+pub fn ctr<C: BlockCipher>(
+    cipher: C,
+    nonce: &[u8],
+    layout: CounterLayout,
+    data: &[u8],
+) -> Result<Vec<u8>, String> {
+    let mut output = data.to_vec();
+    Keystream::new(cipher, nonce, layout)?.apply_keystream(&mut output)?;
+    Ok(output)
+}
@@ -0,0 +1,88 @@
+use crate::set1;
+
+// which columns of the ciphertexts are used to guess the keystream
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    // cut every ciphertext to the shortest one, so every column has a byte from every ciphertext
+    TruncateToShortest,
+    // keep going up to the longest ciphertext, the last columns have only a few bytes to go on
+    Longest,
+}
+
+// a keystream guess for ciphertexts that were all encrypted under the same CTR keystream
+pub struct KeystreamRecovery {
+    ciphertexts: Vec<Vec<u8>>,
+    // None where nothing is known about the keystream byte yet
+    keystream: Vec<Option<u8>>,
+}
+
+impl KeystreamRecovery {
+    // every keystream byte is the single byte xor key that makes its column look most like English
+    pub fn statistical(ciphertexts: &[Vec<u8>], alignment: Alignment) -> KeystreamRecovery {
+        let keystream_length = match alignment {
+            Alignment::TruncateToShortest => ciphertexts.iter().map(Vec::len).min(),
+            Alignment::Longest => ciphertexts.iter().map(Vec::len).max(),
+        }
+        .unwrap_or(0);
+        let rows: Vec<Vec<u8>> = ciphertexts
+            .iter()
+            .map(|ciphertext| ciphertext.iter().take(keystream_length).cloned().collect())
+            .collect();
+        let keystream = set1::transpose_matrix(rows, keystream_length)
+            .iter()
+            .map(|column| Some(set1::decrypt_single_byte_xor_english(column).1))
+            .collect();
+        KeystreamRecovery {
+            ciphertexts: ciphertexts.to_vec(),
+            keystream,
+        }
+    }
+
+    pub fn keystream(&self) -> &[Option<u8>] {
+        &self.keystream
+    }
+
+    // every ciphertext decrypted up to the first keystream byte we know nothing about
+    pub fn plaintexts(&self) -> Vec<Vec<u8>> {
+        self.ciphertexts
+            .iter()
+            .map(|ciphertext| {
+                ciphertext
+                    .iter()
+                    .zip(&self.keystream)
+                    .map_while(|(&byte, &key)| key.map(|key| byte ^ key))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // we know (or guess) that ciphertext number row decrypts to known at offset, which pins down
+    // those keystream bytes for every other ciphertext as well
+    pub fn guess_plaintext(
+        &mut self,
+        row: usize,
+        offset: usize,
+        known: &[u8],
+    ) -> Result<(), String> {
+        let ciphertext = self
+            .ciphertexts
+            .get(row)
+            .ok_or_else(|| format!("No ciphertext number {}", row))?;
+        let end = offset + known.len();
+        if end > ciphertext.len() {
+            return Err(format!(
+                "Ciphertext number {} is only {} bytes long",
+                row,
+                ciphertext.len()
+            ));
+        }
+        let keystream_bytes = set1::fixed_xor(&ciphertext[offset..end], known);
+        if self.keystream.len() < end {
+            self.keystream.resize(end, None);
+        }
+        for (slot, &byte) in self.keystream[offset..end].iter_mut().zip(&keystream_bytes) {
+            *slot = Some(byte);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+// linear algebra over GF(2), on bit vectors packed into u64 words
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitVector {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVector {
+    pub fn zeros(len: usize) -> BitVector {
+        BitVector {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        self.words[index / 64] >> (index % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        let mask = 1 << (index % 64);
+        if value {
+            self.words[index / 64] |= mask;
+        } else {
+            self.words[index / 64] &= !mask;
+        }
+    }
+
+    pub fn xor_assign(&mut self, other: &BitVector) {
+        for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+            *word ^= other_word;
+        }
+    }
+
+    // the lowest set bit at or after start
+    pub fn first_set_from(&self, start: usize) -> Option<usize> {
+        let mut word_index = start / 64;
+        let mut word = self.words.get(word_index)? & (!0 << (start % 64));
+        loop {
+            if word != 0 {
+                return Some(word_index * 64 + word.trailing_zeros() as usize);
+            }
+            word_index += 1;
+            word = *self.words.get(word_index)?;
+        }
+    }
+
+    // the parity of the bits set in both vectors
+    pub fn dot(&self, other: &BitVector) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .fold(0, |acc, (word, other_word)| {
+                acc ^ (word & other_word).count_ones()
+            })
+            & 1
+            == 1
+    }
+}
+
+// a system of linear equations, kept in row echelon form as equations are added
+pub struct LinearSystem {
+    variable_count: usize,
+    // pivots[i] is the equation whose lowest variable is i, if there is one
+    pivots: Vec<Option<(BitVector, bool)>>,
+    rank: usize,
+    inconsistent: bool,
+}
+
+impl LinearSystem {
+    pub fn new(variable_count: usize) -> LinearSystem {
+        LinearSystem {
+            variable_count,
+            pivots: vec![None; variable_count],
+            rank: 0,
+            inconsistent: false,
+        }
+    }
+
+    // coefficients . x = constant. returns false if the equation didn't add any information
+    pub fn add_equation(&mut self, mut coefficients: BitVector, mut constant: bool) -> bool {
+        assert_eq!(coefficients.len(), self.variable_count);
+        let mut start = 0;
+        while let Some(variable) = coefficients.first_set_from(start) {
+            match &self.pivots[variable] {
+                Some((pivot, pivot_constant)) => {
+                    coefficients.xor_assign(pivot);
+                    constant ^= pivot_constant;
+                    start = variable + 1;
+                }
+                None => {
+                    self.pivots[variable] = Some((coefficients, constant));
+                    self.rank += 1;
+                    return true;
+                }
+            }
+        }
+        // 0 = constant: redundant, or a contradiction
+        self.inconsistent |= constant;
+        false
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    // one solution (free variables are 0), or None if the equations contradict each other
+    pub fn solve(&self) -> Option<BitVector> {
+        if self.inconsistent {
+            return None;
+        }
+        let mut solution = BitVector::zeros(self.variable_count);
+        for (variable, pivot) in self.pivots.iter().enumerate().rev() {
+            if let Some((coefficients, constant)) = pivot {
+                // every variable after this one is already solved, and this one is still 0
+                solution.set(variable, constant ^ coefficients.dot(&solution));
+            }
+        }
+        Some(solution)
+    }
+}
@@ -0,0 +1,7 @@
+pub mod ctr;
+pub mod fixed_nonce_ctr;
+pub mod gf2;
+pub mod mt19937;
+pub mod mt19937_cipher;
+pub mod padding_oracle;
+pub mod timestamp_seed;
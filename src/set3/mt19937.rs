@@ -0,0 +1,425 @@
+// the Mersenne Twister, as in the reference mt19937ar.c and mt19937-64.c
+use super::gf2::{BitVector, LinearSystem};
+use rand::{Error, RngCore, SeedableRng};
+
+const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908_b0df;
+const UPPER_MASK: u32 = 0x8000_0000;
+const LOWER_MASK: u32 = 0x7fff_ffff;
+
+#[derive(Clone)]
+pub struct Mt19937 {
+    state: [u32; N],
+    index: usize,
+}
+
+impl Mt19937 {
+    pub fn new(seed: u32) -> Mt19937 {
+        let mut mt = Mt19937 {
+            state: [0; N],
+            index: N,
+        };
+        mt.seed(seed);
+        mt
+    }
+
+    // init_genrand
+    pub fn seed(&mut self, seed: u32) {
+        self.state[0] = seed;
+        for i in 1..N {
+            let previous = self.state[i - 1];
+            self.state[i] = 1_812_433_253u32
+                .wrapping_mul(previous ^ (previous >> 30))
+                .wrapping_add(i as u32);
+        }
+        self.index = N;
+    }
+
+    pub fn init_by_array(&mut self, key: &[u32]) {
+        self.seed(19_650_218);
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..N.max(key.len()) {
+            let previous = self.state[i - 1];
+            self.state[i] = (self.state[i] ^ (previous ^ (previous >> 30)).wrapping_mul(1_664_525))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                self.state[0] = self.state[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..N - 1 {
+            let previous = self.state[i - 1];
+            self.state[i] = (self.state[i]
+                ^ (previous ^ (previous >> 30)).wrapping_mul(1_566_083_941))
+            .wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                self.state[0] = self.state[N - 1];
+                i = 1;
+            }
+        }
+        self.state[0] = 0x8000_0000;
+        self.index = N;
+    }
+
+    // generate the next N words of state at once
+    fn twist(&mut self) {
+        for i in 0..N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ ((y & 1) * MATRIX_A);
+        }
+        self.index = 0;
+    }
+
+    // genrand_int32
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let output = temper(self.state[self.index]);
+        self.index += 1;
+        output
+    }
+
+    // the generator that produced outputs, positioned just after them.
+    // the outputs have to start right after a twist, as they do for a freshly seeded generator
+    pub fn clone_from_outputs(outputs: &[u32; N]) -> Mt19937 {
+        let mut state = [0; N];
+        for (word, &output) in state.iter_mut().zip(outputs.iter()) {
+            *word = untemper(output);
+        }
+        Mt19937 { state, index: N }
+    }
+
+    // like clone_from_outputs, but only the bits of each output in known_mask were observed.
+    // the outputs after the first N pin down the unknown bits of the first N, so enough of
+    // them are needed to give one equation per unknown bit (about 3 * N more for the top 8 bits)
+    pub fn clone_from_truncated_outputs(
+        outputs: &[u32],
+        known_mask: u32,
+    ) -> Result<Mt19937, String> {
+        if outputs.len() < N {
+            return Err(format!(
+                "need at least {} outputs, got {}",
+                N,
+                outputs.len()
+            ));
+        }
+        let unknown_bits: Vec<usize> = (0..32).filter(|bit| known_mask >> bit & 1 == 0).collect();
+        // every bit of state is an xor of unknown bits and the constant 1, which is the last variable
+        let constant = N * unknown_bits.len();
+        let variable_count = constant + 1;
+        let mut state: Vec<Vec<BitVector>> = outputs[..N]
+            .iter()
+            .enumerate()
+            .map(|(i, &output)| {
+                let tempered: Vec<BitVector> = (0..32)
+                    .map(|bit| {
+                        let mut expression = BitVector::zeros(variable_count);
+                        match unknown_bits.iter().position(|&unknown| unknown == bit) {
+                            Some(k) => expression.set(i * unknown_bits.len() + k, true),
+                            None => expression.set(constant, output >> bit & 1 == 1),
+                        }
+                        expression
+                    })
+                    .collect();
+                apply_linear(&tempered, untemper)
+            })
+            .collect();
+
+        let mut system = LinearSystem::new(variable_count);
+        let mut one = BitVector::zeros(variable_count);
+        one.set(constant, true);
+        system.add_equation(one, true);
+        for (i, &output) in outputs.iter().enumerate().skip(N) {
+            if i % N == 0 {
+                twist_symbolically(&mut state);
+            }
+            let tempered = apply_linear(&state[i % N], temper);
+            for (bit, expression) in tempered.into_iter().enumerate() {
+                if known_mask >> bit & 1 == 1 {
+                    system.add_equation(expression, output >> bit & 1 == 1);
+                }
+            }
+        }
+
+        // the low 31 bits of the first word of state never affect later outputs, so the unknown
+        // bits of the first output that only reach those can't be (and needn't be) determined
+        let first_word_top_bit = unknown_bits
+            .iter()
+            .any(|&bit| untemper(1 << bit) & UPPER_MASK != 0);
+        let undeterminable = unknown_bits.len() - first_word_top_bit as usize;
+        if system.rank() < variable_count - undeterminable {
+            return Err(String::from("not enough outputs to determine the state"));
+        }
+        let solution = system
+            .solve()
+            .ok_or_else(|| String::from("no MT19937 state produces these outputs"))?;
+
+        let mut clone = Mt19937 {
+            state: [0; N],
+            index: 0,
+        };
+        for (i, (word, &output)) in clone.state.iter_mut().zip(outputs.iter()).enumerate() {
+            let mut tempered = output & known_mask;
+            for (k, &bit) in unknown_bits.iter().enumerate() {
+                if solution.get(i * unknown_bits.len() + k) {
+                    tempered |= 1 << bit;
+                }
+            }
+            *word = untemper(tempered);
+        }
+        // replay the observed outputs, leaving the clone just after them
+        for &output in outputs {
+            if (clone.next_u32() ^ output) & known_mask != 0 {
+                return Err(String::from("no MT19937 state produces these outputs"));
+            }
+        }
+        Ok(clone)
+    }
+}
+
+// twist, on state whose bits are expressions over GF(2)
+fn twist_symbolically(state: &mut [Vec<BitVector>]) {
+    for i in 0..N {
+        // y is the top bit of state[i] and the low 31 bits of state[i + 1]
+        let y = |bit: usize| {
+            if bit == 31 {
+                &state[i][31]
+            } else {
+                &state[(i + 1) % N][bit]
+            }
+        };
+        let mut next = state[(i + M) % N].clone();
+        for (bit, expression) in next.iter_mut().enumerate() {
+            if bit < 31 {
+                expression.xor_assign(y(bit + 1));
+            }
+            if MATRIX_A >> bit & 1 == 1 {
+                expression.xor_assign(y(0));
+            }
+        }
+        state[i] = next;
+    }
+}
+
+// f applied to a word whose bits are expressions over GF(2). f has to be linear
+fn apply_linear(word: &[BitVector], f: fn(u32) -> u32) -> Vec<BitVector> {
+    let mut result = vec![BitVector::zeros(word[0].len()); 32];
+    for (bit, expression) in word.iter().enumerate() {
+        let image = f(1 << bit);
+        for (image_bit, output) in result.iter_mut().enumerate() {
+            if image >> image_bit & 1 == 1 {
+                output.xor_assign(expression);
+            }
+        }
+    }
+    result
+}
+
+pub fn temper(mut y: u32) -> u32 {
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9d2c_5680;
+    y ^= (y << 15) & 0xefc6_0000;
+    y ^ (y >> 18)
+}
+
+pub fn untemper(mut y: u32) -> u32 {
+    y = undo_right_shift_xor(y, 18);
+    y = undo_left_shift_xor(y, 15, 0xefc6_0000);
+    y = undo_left_shift_xor(y, 7, 0x9d2c_5680);
+    undo_right_shift_xor(y, 11)
+}
+
+// invert y ^= y >> shift; each round recovers another shift bits from the top
+fn undo_right_shift_xor(y: u32, shift: u32) -> u32 {
+    let mut x = y;
+    for _ in 0..32 / shift {
+        x = y ^ (x >> shift);
+    }
+    x
+}
+
+// invert y ^= (y << shift) & mask; each round recovers another shift bits from the bottom
+fn undo_left_shift_xor(y: u32, shift: u32, mask: u32) -> u32 {
+    let mut x = y;
+    for _ in 0..32 / shift {
+        x = y ^ ((x << shift) & mask);
+    }
+    x
+}
+
+impl Default for Mt19937 {
+    // the reference implementation's default seed
+    fn default() -> Mt19937 {
+        Mt19937::new(5489)
+    }
+}
+
+impl RngCore for Mt19937 {
+    fn next_u32(&mut self) -> u32 {
+        Mt19937::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let low = Mt19937::next_u32(self) as u64;
+        let high = Mt19937::next_u32(self) as u64;
+        (high << 32) | low
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = Mt19937::next_u32(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Mt19937 {
+    type Seed = [u8; 4];
+
+    fn from_seed(seed: [u8; 4]) -> Mt19937 {
+        Mt19937::new(u32::from_le_bytes(seed))
+    }
+}
+
+const NN: usize = 312;
+const MM: usize = 156;
+const MATRIX_A_64: u64 = 0xb502_6f5a_a966_19e9;
+const UPPER_MASK_64: u64 = 0xffff_ffff_8000_0000;
+const LOWER_MASK_64: u64 = 0x7fff_ffff;
+
+#[derive(Clone)]
+pub struct Mt19937_64 {
+    state: [u64; NN],
+    index: usize,
+}
+
+impl Mt19937_64 {
+    pub fn new(seed: u64) -> Mt19937_64 {
+        let mut mt = Mt19937_64 {
+            state: [0; NN],
+            index: NN,
+        };
+        mt.seed(seed);
+        mt
+    }
+
+    // init_genrand64
+    pub fn seed(&mut self, seed: u64) {
+        self.state[0] = seed;
+        for i in 1..NN {
+            let previous = self.state[i - 1];
+            self.state[i] = 6_364_136_223_846_793_005u64
+                .wrapping_mul(previous ^ (previous >> 62))
+                .wrapping_add(i as u64);
+        }
+        self.index = NN;
+    }
+
+    // init_by_array64
+    pub fn init_by_array(&mut self, key: &[u64]) {
+        self.seed(19_650_218);
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..NN.max(key.len()) {
+            let previous = self.state[i - 1];
+            self.state[i] = (self.state[i]
+                ^ (previous ^ (previous >> 62)).wrapping_mul(3_935_559_000_370_003_845))
+            .wrapping_add(key[j])
+            .wrapping_add(j as u64);
+            i += 1;
+            j += 1;
+            if i >= NN {
+                self.state[0] = self.state[NN - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..NN - 1 {
+            let previous = self.state[i - 1];
+            self.state[i] = (self.state[i]
+                ^ (previous ^ (previous >> 62)).wrapping_mul(2_862_933_555_777_941_757))
+            .wrapping_sub(i as u64);
+            i += 1;
+            if i >= NN {
+                self.state[0] = self.state[NN - 1];
+                i = 1;
+            }
+        }
+        self.state[0] = 1 << 63;
+        self.index = NN;
+    }
+
+    fn twist(&mut self) {
+        for i in 0..NN {
+            let x = (self.state[i] & UPPER_MASK_64) | (self.state[(i + 1) % NN] & LOWER_MASK_64);
+            self.state[i] = self.state[(i + MM) % NN] ^ (x >> 1) ^ ((x & 1) * MATRIX_A_64);
+        }
+        self.index = 0;
+    }
+
+    // genrand64_int64
+    pub fn next_u64(&mut self) -> u64 {
+        if self.index >= NN {
+            self.twist();
+        }
+        let mut x = self.state[self.index];
+        self.index += 1;
+        x ^= (x >> 29) & 0x5555_5555_5555_5555;
+        x ^= (x << 17) & 0x71d6_7fff_eda6_0000;
+        x ^= (x << 37) & 0xfff7_eee0_0000_0000;
+        x ^ (x >> 43)
+    }
+}
+
+impl Default for Mt19937_64 {
+    fn default() -> Mt19937_64 {
+        Mt19937_64::new(5489)
+    }
+}
+
+impl RngCore for Mt19937_64 {
+    fn next_u32(&mut self) -> u32 {
+        Mt19937_64::next_u64(self) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Mt19937_64::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = Mt19937_64::next_u64(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Mt19937_64 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: [u8; 8]) -> Mt19937_64 {
+        Mt19937_64::new(u64::from_le_bytes(seed))
+    }
+}
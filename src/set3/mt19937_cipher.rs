@@ -0,0 +1,57 @@
+// a stream cipher whose keystream is the output of MT19937, keyed by a 16-bit seed
+use super::mt19937::Mt19937;
+use super::timestamp_seed::{self, Clock};
+use crate::set2;
+use rand::{Rng, RngCore};
+
+// xors data with the keystream; encryption and decryption are the same
+pub fn apply_keystream(seed: u16, data: &[u8]) -> Vec<u8> {
+    let mut keystream = vec![0; data.len()];
+    Mt19937::new(seed as u32).fill_bytes(&mut keystream);
+    data.iter().zip(keystream).map(|(d, k)| d ^ k).collect()
+}
+
+// challenge 24: encrypt plaintext after 5-20 random bytes
+pub fn encrypt_with_random_prefix(seed: u16, plaintext: &[u8]) -> Vec<u8> {
+    let mut data = set2::random_bytes(rand::thread_rng().gen_range(5..=20));
+    data.extend_from_slice(plaintext);
+    apply_keystream(seed, &data)
+}
+
+// the seed of a ciphertext whose plaintext ends with known_suffix
+pub fn recover_seed(ciphertext: &[u8], known_suffix: &[u8]) -> Option<u16> {
+    // an empty suffix matches under every seed
+    if known_suffix.is_empty() || known_suffix.len() > ciphertext.len() {
+        return None;
+    }
+    let offset = ciphertext.len() - known_suffix.len();
+    let mut keystream = vec![0; ciphertext.len()];
+    (0..=u16::MAX).find(|&seed| {
+        Mt19937::new(seed as u32).fill_bytes(&mut keystream);
+        ciphertext[offset..]
+            .iter()
+            .zip(&keystream[offset..])
+            .map(|(c, k)| c ^ k)
+            .eq(known_suffix.iter().cloned())
+    })
+}
+
+// a password reset token from an MT19937 seeded with the current time
+pub fn password_reset_token(clock: &dyn Clock) -> [u8; 16] {
+    let mut token = [0; 16];
+    Mt19937::new(clock.now() as u32).fill_bytes(&mut token);
+    token
+}
+
+// whether token is what password_reset_token would have given at some point in the last max_age seconds
+pub fn is_time_seeded_token(token: &[u8], clock: &dyn Clock, max_age: u64) -> bool {
+    // every seed produces the empty token, so it says nothing about how a token was made
+    if token.is_empty() {
+        return false;
+    }
+    let mut candidate = vec![0; token.len()];
+    timestamp_seed::recent_seeds(clock, max_age).any(|seed| {
+        Mt19937::new(seed).fill_bytes(&mut candidate);
+        candidate == token
+    })
+}
@@ -0,0 +1,150 @@
+use crate::set1;
+use crate::set1::aes::Aes;
+use crate::set1::block_cipher::BlockCipher;
+use crate::set2;
+use crate::set2::{cbc, pkcs7};
+use rand::Rng;
+use std::cell::Cell;
+
+const BLOCK_SIZE: usize = 16;
+
+pub const CHALLENGE_17_STRINGS: [&str; 10] = [
+    "MDAwMDAwTm93IHRoYXQgdGhlIHBhcnR5IGlzIGp1bXBpbmc=",
+    "MDAwMDAxV2l0aCB0aGUgYmFzcyBraWNrZWQgaW4gYW5kIHRoZSBWZWdhJ3MgYXJlIHB1bXBpbic=",
+    "MDAwMDAyUXVpY2sgdG8gdGhlIHBvaW50LCB0byB0aGUgcG9pbnQsIG5vIGZha2luZw==",
+    "MDAwMDAzQ29va2luZyBNQydzIGxpa2UgYSBwb3VuZCBvZiBiYWNvbg==",
+    "MDAwMDA0QnVybmluZyAnZW0sIGlmIHlvdSBhaW4ndCBxdWljayBhbmQgbmltYmxl",
+    "MDAwMDA1SSBnbyBjcmF6eSB3aGVuIEkgaGVhciBhIGN5bWJhbA==",
+    "MDAwMDA2QW5kIGEgaGlnaCBoYXQgd2l0aCBhIHNvdXBlZCB1cCB0ZW1wbw==",
+    "MDAwMDA3SSdtIG9uIGEgcm9sbCwgaXQncyB0aW1lIHRvIGdvIHNvbG8=",
+    "MDAwMDA4b2xsaW4nIGluIG15IGZpdmUgcG9pbnQgb2g=",
+    "MDAwMDA5aXRoIG15IHJhZy10b3AgZG93biBzbyBteSBoYWlyIGNhbiBibG93",
+];
+
+// encrypts under AES-CBC with a random IV, and tells anyone whether a ciphertext's padding is valid
+pub struct PaddingOracleService {
+    aes: Aes,
+}
+
+impl PaddingOracleService {
+    pub fn new() -> PaddingOracleService {
+        PaddingOracleService {
+            aes: Aes::new(&set2::random_aes_key()).unwrap(),
+        }
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> (Vec<u8>, [u8; 16]) {
+        let iv: [u8; 16] = rand::thread_rng().gen();
+        (cbc::encrypt(&self.aes, &iv, plaintext), iv)
+    }
+
+    // one of the challenge's strings, chosen at random
+    pub fn encrypt_random_string(&self) -> (Vec<u8>, [u8; 16]) {
+        let index = rand::thread_rng().gen_range(0..CHALLENGE_17_STRINGS.len());
+        self.encrypt(&set1::base64_decode(CHALLENGE_17_STRINGS[index]))
+    }
+
+    pub fn has_valid_padding(&self, ciphertext: &[u8], iv: &[u8; 16]) -> bool {
+        cbc::decrypt(&self.aes, iv, ciphertext).is_ok()
+    }
+}
+
+impl Default for PaddingOracleService {
+    fn default() -> PaddingOracleService {
+        PaddingOracleService::new()
+    }
+}
+
+// reported after every decrypted block
+pub struct BlockProgress<'a> {
+    pub block_index: usize,
+    pub block_count: usize,
+    pub plaintext_block: &'a [u8],
+    // oracle queries made so far, in total
+    pub query_count: usize,
+}
+
+pub struct Decryption {
+    // with the padding removed
+    pub plaintext: Vec<u8>,
+    pub query_count: usize,
+}
+
+// recover D(block) by forging the "IV" in front of it one byte at a time, from the last byte on:
+// once the forged IV makes the plaintext end in n bytes of value n, D(block) = forged ^ n there
+fn decrypt_block<F: Fn(&[u8], &[u8; 16]) -> bool>(
+    oracle: &F,
+    block: &[u8],
+) -> Result<[u8; 16], String> {
+    let mut intermediate = [0; BLOCK_SIZE];
+    for padding in 1..=BLOCK_SIZE {
+        let position = BLOCK_SIZE - padding;
+        let mut forged_iv = [0; BLOCK_SIZE];
+        for i in position + 1..BLOCK_SIZE {
+            forged_iv[i] = intermediate[i] ^ padding as u8;
+        }
+        let guess = (0..=255).find(|&guess| {
+            forged_iv[position] = guess;
+            if !oracle(block, &forged_iv) {
+                return false;
+            }
+            // when looking for the last byte, a plaintext ending in 0x02 0x02 (and so on) is also
+            // valid. changing the byte before it only breaks the padding in that case
+            if padding == 1 {
+                let mut check_iv = forged_iv;
+                check_iv[position - 1] ^= 1;
+                return oracle(block, &check_iv);
+            }
+            true
+        });
+        match guess {
+            Some(guess) => intermediate[position] = guess ^ padding as u8,
+            None => return Err(format!("No valid padding found for byte {}", position)),
+        }
+    }
+    Ok(intermediate)
+}
+
+// decrypt a whole ciphertext, including its first block (via the IV)
+pub fn decrypt<F, P>(
+    oracle: F,
+    ciphertext: &[u8],
+    iv: &[u8; 16],
+    mut progress: P,
+) -> Result<Decryption, String>
+where
+    F: Fn(&[u8], &[u8; 16]) -> bool,
+    P: FnMut(&BlockProgress),
+{
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(String::from("Invalid ciphertext length"));
+    }
+    let query_count = Cell::new(0);
+    let counting_oracle = |ciphertext: &[u8], iv: &[u8; 16]| {
+        query_count.set(query_count.get() + 1);
+        oracle(ciphertext, iv)
+    };
+
+    let block_count = ciphertext.len() / BLOCK_SIZE;
+    let mut previous_block: &[u8] = iv;
+    let mut padded_plaintext = Vec::with_capacity(ciphertext.len());
+    for (block_index, block) in ciphertext.chunks(BLOCK_SIZE).enumerate() {
+        let intermediate = decrypt_block(&counting_oracle, block)?;
+        let plaintext_block = set1::fixed_xor(&intermediate, previous_block);
+        progress(&BlockProgress {
+            block_index,
+            block_count,
+            plaintext_block: &plaintext_block,
+            query_count: query_count.get(),
+        });
+        padded_plaintext.extend(plaintext_block);
+        previous_block = block;
+    }
+
+    let plaintext =
+        pkcs7::unpad(&padded_plaintext, BLOCK_SIZE).map_err(|error| error.to_string())?;
+    Ok(Decryption {
+        plaintext,
+        query_count: query_count.get(),
+    })
+}
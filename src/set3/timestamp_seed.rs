@@ -0,0 +1,98 @@
+// recovering the seed of an MT19937 seeded with the time
+use super::mt19937::Mt19937;
+use rand::Rng;
+use std::cell::Cell;
+use std::ops::RangeInclusive;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub trait Clock {
+    // seconds since the Unix epoch
+    fn now(&self) -> u64;
+    fn sleep(&self, seconds: u64);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the clock is before 1970")
+            .as_secs()
+    }
+
+    fn sleep(&self, seconds: u64) {
+        thread::sleep(Duration::from_secs(seconds));
+    }
+}
+
+// a clock that only moves when slept on, so tests don't have to wait
+pub struct SimulatedClock {
+    now: Cell<u64>,
+}
+
+impl SimulatedClock {
+    pub fn new(now: u64) -> SimulatedClock {
+        SimulatedClock {
+            now: Cell::new(now),
+        }
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> u64 {
+        self.now.get()
+    }
+
+    fn sleep(&self, seconds: u64) {
+        self.now.set(self.now.get() + seconds);
+    }
+}
+
+// challenge 22: wait, seed with the time, wait some more, then hand out the first output
+pub fn first_output_after_waiting(clock: &dyn Clock) -> u32 {
+    let mut rng = rand::thread_rng();
+    clock.sleep(rng.gen_range(40..=1000));
+    let mut mt = Mt19937::new(clock.now() as u32);
+    clock.sleep(rng.gen_range(40..=1000));
+    mt.next_u32()
+}
+
+// the seeds of generators seeded up to max_age seconds ago
+pub fn recent_seeds(clock: &dyn Clock, max_age: u64) -> RangeInclusive<u32> {
+    let now = clock.now();
+    now.saturating_sub(max_age) as u32..=now as u32
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SeedMatch {
+    pub seed: u32,
+    // how many outputs the generator had produced before the observed ones
+    pub offset: usize,
+}
+
+// find a seed in seeds whose generator produces observed, starting at most max_offset outputs in.
+// the newest seeds are tried first, since they're the likeliest
+pub fn recover_seed(
+    observed: &[u32],
+    seeds: RangeInclusive<u32>,
+    max_offset: usize,
+) -> Option<SeedMatch> {
+    if observed.is_empty() {
+        return None;
+    }
+    let mut outputs = Vec::with_capacity(max_offset + observed.len());
+    for seed in seeds.rev() {
+        let mut mt = Mt19937::new(seed);
+        outputs.clear();
+        outputs.extend((0..max_offset + observed.len()).map(|_| mt.next_u32()));
+        if let Some(offset) = outputs
+            .windows(observed.len())
+            .position(|window| window == observed)
+        {
+            return Some(SeedMatch { seed, offset });
+        }
+    }
+    None
+}
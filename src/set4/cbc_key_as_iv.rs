@@ -0,0 +1,106 @@
+// challenge 27: CBC with the key reused as the IV, and an error message that leaks plaintext
+use crate::set1;
+use crate::set1::aes::Aes;
+use crate::set1::block_cipher::BlockCipher;
+use crate::set2;
+use crate::set2::cbc;
+use crate::set2::pkcs7::{self, PaddingError};
+use crate::set2::userdata::UserdataCipher;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecryptionError {
+    Padding(PaddingError),
+    // the plaintext has bytes above 0x7f. the error hands back the plaintext (still padded)
+    HighAscii(Vec<u8>),
+}
+
+impl fmt::Display for DecryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecryptionError::Padding(error) => error.fmt(f),
+            DecryptionError::HighAscii(plaintext) => write!(
+                f,
+                "Plaintext isn't ASCII: {}",
+                String::from_utf8_lossy(plaintext)
+            ),
+        }
+    }
+}
+
+impl Error for DecryptionError {}
+
+impl From<PaddingError> for DecryptionError {
+    fn from(error: PaddingError) -> DecryptionError {
+        DecryptionError::Padding(error)
+    }
+}
+
+// AES-CBC with IV = key, checking that plaintexts are ASCII before unpadding them
+pub struct KeyAsIvCipher {
+    aes: Aes,
+    key: [u8; 16],
+}
+
+impl KeyAsIvCipher {
+    pub fn new() -> KeyAsIvCipher {
+        KeyAsIvCipher::with_key(set2::random_aes_key())
+    }
+
+    pub fn with_key(key: [u8; 16]) -> KeyAsIvCipher {
+        KeyAsIvCipher {
+            aes: Aes::new(&key).unwrap(),
+            key,
+        }
+    }
+}
+
+impl Default for KeyAsIvCipher {
+    fn default() -> KeyAsIvCipher {
+        KeyAsIvCipher::new()
+    }
+}
+
+impl UserdataCipher for KeyAsIvCipher {
+    type Error = DecryptionError;
+
+    fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        cbc::encrypt(&self.aes, &self.key, plaintext)
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, DecryptionError> {
+        if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(self.aes.block_size()) {
+            return Err(PaddingError::BadLength.into());
+        }
+        let plaintext = cbc::decrypt_blocks(&self.aes, &self.key, ciphertext);
+        if !plaintext.is_ascii() {
+            return Err(DecryptionError::HighAscii(plaintext));
+        }
+        Ok(pkcs7::unpad(&plaintext, self.aes.block_size())?)
+    }
+}
+
+// decrypting C1 || 0 || C1 gives P'1 = D(C1) ^ key and P'3 = D(C1) ^ 0, so P'1 ^ P'3 = key.
+// P'2 is garbage, so the service almost certainly rejects the plaintext and shows it to us
+pub fn recover_key<F>(ciphertext: &[u8], decrypt: F) -> Result<[u8; 16], String>
+where
+    F: Fn(&[u8]) -> Result<Vec<u8>, DecryptionError>,
+{
+    if ciphertext.len() < 3 * 16 {
+        return Err(String::from("Need a ciphertext of at least 3 blocks"));
+    }
+    let first_block = &ciphertext[..16];
+    let mut modified = first_block.to_vec();
+    modified.extend_from_slice(&[0; 16]);
+    modified.extend_from_slice(first_block);
+
+    match decrypt(&modified) {
+        Err(DecryptionError::HighAscii(plaintext)) => {
+            let key = set1::fixed_xor(&plaintext[..16], &plaintext[32..48]);
+            Ok(*arrayref::array_ref!(key, 0, 16))
+        }
+        Err(error) => Err(format!("The service didn't leak the plaintext: {}", error)),
+        Ok(_) => Err(String::from("The modified ciphertext decrypted to ASCII")),
+    }
+}
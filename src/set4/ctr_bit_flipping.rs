@@ -0,0 +1,69 @@
+// challenge 26: the challenge 16 userdata service under CTR, where bit flips need no sacrificial block
+use crate::set1;
+use crate::set1::aes::Aes;
+use crate::set1::block_cipher::BlockCipher;
+use crate::set2;
+use crate::set2::userdata::{BitFlipForgery, UserdataCipher, ADMIN_TOKEN, PREFIX};
+use crate::set3::ctr::{self, CounterLayout};
+use rand::Rng;
+use std::convert::Infallible;
+
+// AES-CTR under a random key and nonce
+pub struct CtrCipher {
+    aes: Aes,
+    nonce: [u8; 8],
+}
+
+impl CtrCipher {
+    pub fn new() -> CtrCipher {
+        CtrCipher {
+            aes: Aes::new(&set2::random_aes_key()).unwrap(),
+            nonce: rand::thread_rng().gen(),
+        }
+    }
+
+    fn apply_keystream(&self, data: &[u8]) -> Vec<u8> {
+        ctr::ctr(
+            self.aes.clone(),
+            &self.nonce,
+            CounterLayout::LittleEndian64,
+            data,
+        )
+        .unwrap()
+    }
+}
+
+impl Default for CtrCipher {
+    fn default() -> CtrCipher {
+        CtrCipher::new()
+    }
+}
+
+impl UserdataCipher for CtrCipher {
+    // without padding, any ciphertext decrypts
+    type Error = Infallible;
+
+    fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        self.apply_keystream(plaintext)
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Infallible> {
+        Ok(self.apply_keystream(ciphertext))
+    }
+}
+
+// send a placeholder as long as the token and xor placeholder ^ token straight into its
+// ciphertext: flipping a CTR ciphertext bit flips the same plaintext bit and nothing else
+pub fn forge_admin<F: Fn(&str) -> Vec<u8>>(encrypt_userdata: F) -> BitFlipForgery {
+    let placeholder = "A".repeat(ADMIN_TOKEN.len());
+    let mut ciphertext = encrypt_userdata(&placeholder);
+    let flip_range = PREFIX.len()..PREFIX.len() + ADMIN_TOKEN.len();
+    let mask = set1::fixed_xor(placeholder.as_bytes(), ADMIN_TOKEN.as_bytes());
+    let flipped = set1::fixed_xor(&ciphertext[flip_range.clone()], &mask);
+    ciphertext[flip_range].copy_from_slice(&flipped);
+
+    BitFlipForgery {
+        ciphertext,
+        scrambled: PREFIX.len()..PREFIX.len(),
+    }
+}
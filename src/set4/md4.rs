@@ -0,0 +1,92 @@
+// MD4 (RFC 1320), whose state is read from and written to the digest as little-endian words
+use super::merkle_damgard::{self, ByteOrder, MerkleDamgard};
+
+pub const BLOCK_SIZE: usize = 64;
+pub const DIGEST_SIZE: usize = 16;
+// A, B, C, D
+pub const INITIAL_STATE: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+#[derive(Clone, Copy, Debug)]
+pub struct Md4;
+
+impl MerkleDamgard for Md4 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    const LENGTH_SIZE: usize = 8;
+    const LENGTH_BYTE_ORDER: ByteOrder = ByteOrder::LittleEndian;
+
+    type State = [u32; 4];
+    type Digest = [u8; DIGEST_SIZE];
+
+    const INITIAL_STATE: [u32; 4] = INITIAL_STATE;
+
+    fn compress(state: &mut [u32; 4], block: &[u8]) {
+        compress(state, arrayref::array_ref!(block, 0, BLOCK_SIZE))
+    }
+
+    fn state_to_digest(state: &[u32; 4]) -> [u8; DIGEST_SIZE] {
+        let mut digest = [0; DIGEST_SIZE];
+        for (bytes, word) in digest.chunks_mut(4).zip(state.iter()) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+
+    fn state_from_digest(digest: &[u8; DIGEST_SIZE]) -> [u32; 4] {
+        let mut state = [0; 4];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_le_bytes(*arrayref::array_ref!(bytes, 0, 4));
+        }
+        state
+    }
+}
+
+pub fn compress(state: &mut [u32; 4], block: &[u8; BLOCK_SIZE]) {
+    let mut x = [0u32; 16];
+    for (word, bytes) in x.iter_mut().zip(block.chunks(4)) {
+        *word = u32::from_le_bytes(*arrayref::array_ref!(bytes, 0, 4));
+    }
+
+    let f = |x: u32, y: u32, z: u32| (x & y) | (!x & z);
+    let g = |x: u32, y: u32, z: u32| (x & y) | (x & z) | (y & z);
+    let h = |x: u32, y: u32, z: u32| x ^ y ^ z;
+
+    let [mut a, mut b, mut c, mut d] = *state;
+    // each round updates a, d, c, b in turn, four times over
+    for &i in [0, 4, 8, 12].iter() {
+        a = step(a, f(b, c, d), x[i], 0, 3);
+        d = step(d, f(a, b, c), x[i + 1], 0, 7);
+        c = step(c, f(d, a, b), x[i + 2], 0, 11);
+        b = step(b, f(c, d, a), x[i + 3], 0, 19);
+    }
+    for i in 0..4 {
+        a = step(a, g(b, c, d), x[i], 0x5a82_7999, 3);
+        d = step(d, g(a, b, c), x[i + 4], 0x5a82_7999, 5);
+        c = step(c, g(d, a, b), x[i + 8], 0x5a82_7999, 9);
+        b = step(b, g(c, d, a), x[i + 12], 0x5a82_7999, 13);
+    }
+    for &i in [0, 2, 1, 3].iter() {
+        a = step(a, h(b, c, d), x[i], 0x6ed9_eba1, 3);
+        d = step(d, h(a, b, c), x[i + 8], 0x6ed9_eba1, 9);
+        c = step(c, h(d, a, b), x[i + 4], 0x6ed9_eba1, 11);
+        b = step(b, h(c, d, a), x[i + 12], 0x6ed9_eba1, 15);
+    }
+
+    for (word, added) in state.iter_mut().zip([a, b, c, d].iter()) {
+        *word = word.wrapping_add(*added);
+    }
+}
+
+fn step(word: u32, mixed: u32, x: u32, constant: u32, shift: u32) -> u32 {
+    word.wrapping_add(mixed)
+        .wrapping_add(x)
+        .wrapping_add(constant)
+        .rotate_left(shift)
+}
+
+pub fn md4(data: &[u8]) -> [u8; DIGEST_SIZE] {
+    Md4::hash(data)
+}
+
+pub fn secret_prefix_mac(key: &[u8], message: &[u8]) -> [u8; DIGEST_SIZE] {
+    merkle_damgard::secret_prefix_mac::<Md4>(key, message)
+}
@@ -0,0 +1,180 @@
+// hashes that pad the message with its length and run it block by block through a compression
+// function. the digest is the whole state, so anyone holding one can carry on hashing from it
+use std::fmt::Debug;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    BigEndian,
+    LittleEndian,
+}
+
+// a hash function, described by its compression function and how its state becomes a digest.
+// Hasher does the buffering and padding for all of them
+pub trait MerkleDamgard: Sized {
+    const BLOCK_SIZE: usize;
+    // how many bytes the message length (in bits) takes up at the end of the padding
+    const LENGTH_SIZE: usize;
+    const LENGTH_BYTE_ORDER: ByteOrder;
+
+    type State: Copy + Debug + PartialEq;
+    type Digest: AsRef<[u8]> + Clone + Debug + PartialEq;
+
+    const INITIAL_STATE: Self::State;
+
+    // block is BLOCK_SIZE bytes long
+    fn compress(state: &mut Self::State, block: &[u8]);
+    fn state_to_digest(state: &Self::State) -> Self::Digest;
+    fn state_from_digest(digest: &Self::Digest) -> Self::State;
+
+    fn hash(data: &[u8]) -> Self::Digest {
+        let mut hasher = Hasher::<Self>::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    // the padding appended to a message of message_length bytes: 0x80, zeros, then the length in bits
+    fn padding(message_length: u64) -> Vec<u8> {
+        let used = (message_length % Self::BLOCK_SIZE as u64) as usize + 1 + Self::LENGTH_SIZE;
+        let zeros = (Self::BLOCK_SIZE - used % Self::BLOCK_SIZE) % Self::BLOCK_SIZE;
+        let mut padding = vec![0x80];
+        padding.resize(1 + zeros, 0);
+        let bits = u128::from(message_length) * 8;
+        match Self::LENGTH_BYTE_ORDER {
+            ByteOrder::BigEndian => {
+                padding.extend_from_slice(&bits.to_be_bytes()[16 - Self::LENGTH_SIZE..])
+            }
+            ByteOrder::LittleEndian => {
+                padding.extend_from_slice(&bits.to_le_bytes()[..Self::LENGTH_SIZE])
+            }
+        }
+        padding
+    }
+}
+
+// incremental hashing with any MerkleDamgard hash, which can start from any state
+#[derive(Clone, Debug)]
+pub struct Hasher<H: MerkleDamgard> {
+    state: H::State,
+    // bytes hashed so far, including the ones still in buffer
+    length: u64,
+    buffer: Vec<u8>,
+}
+
+impl<H: MerkleDamgard> Hasher<H> {
+    pub fn new() -> Hasher<H> {
+        Hasher::from_state(H::INITIAL_STATE, 0)
+    }
+
+    // resume from the state after hashing length bytes, which must be a whole number of blocks
+    pub fn from_state(state: H::State, length: u64) -> Hasher<H> {
+        assert!(length.is_multiple_of(H::BLOCK_SIZE as u64));
+        Hasher {
+            state,
+            length,
+            buffer: Vec::with_capacity(H::BLOCK_SIZE),
+        }
+    }
+
+    // resume from the state a digest was read from, as if length bytes had been hashed
+    pub fn from_digest(digest: &H::Digest, length: u64) -> Hasher<H> {
+        Hasher::from_state(H::state_from_digest(digest), length)
+    }
+
+    pub fn state(&self) -> H::State {
+        self.state
+    }
+
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+        if !self.buffer.is_empty() {
+            let taken = data.len().min(H::BLOCK_SIZE - self.buffer.len());
+            self.buffer.extend_from_slice(&data[..taken]);
+            data = &data[taken..];
+            if self.buffer.len() < H::BLOCK_SIZE {
+                return;
+            }
+            H::compress(&mut self.state, &self.buffer);
+            self.buffer.clear();
+        }
+        let mut blocks = data.chunks_exact(H::BLOCK_SIZE);
+        for block in &mut blocks {
+            H::compress(&mut self.state, block);
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+
+    pub fn finalize(mut self) -> H::Digest {
+        let length = self.length;
+        self.update(&H::padding(length));
+        H::state_to_digest(&self.state)
+    }
+}
+
+impl<H: MerkleDamgard> Default for Hasher<H> {
+    fn default() -> Hasher<H> {
+        Hasher::new()
+    }
+}
+
+pub fn secret_prefix_mac<H: MerkleDamgard>(key: &[u8], message: &[u8]) -> H::Digest {
+    let mut hasher = Hasher::<H>::new();
+    hasher.update(key);
+    hasher.update(message);
+    hasher.finalize()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Forgery<D> {
+    pub message: Vec<u8>,
+    pub mac: D,
+    pub key_length: usize,
+}
+
+// the message original || glue padding || extension, and its MAC, assuming the key of the
+// secret-prefix MAC on original is key_length bytes long
+pub fn extend<H: MerkleDamgard>(
+    original: &[u8],
+    mac: &H::Digest,
+    key_length: usize,
+    extension: &[u8],
+) -> (Vec<u8>, H::Digest) {
+    let hashed_length = (key_length + original.len()) as u64;
+    let glue = H::padding(hashed_length);
+    let mut message = original.to_vec();
+    message.extend_from_slice(&glue);
+    message.extend_from_slice(extension);
+
+    let mut hasher = Hasher::<H>::from_digest(mac, hashed_length + glue.len() as u64);
+    hasher.update(extension);
+    (message, hasher.finalize())
+}
+
+// guess key lengths up to max_key_length until verify accepts an extension
+pub fn forge<H, F>(
+    original: &[u8],
+    mac: &H::Digest,
+    extension: &[u8],
+    max_key_length: usize,
+    verify: F,
+) -> Option<Forgery<H::Digest>>
+where
+    H: MerkleDamgard,
+    F: Fn(&[u8], &H::Digest) -> bool,
+{
+    (0..=max_key_length).find_map(|key_length| {
+        let (message, mac) = extend::<H>(original, mac, key_length, extension);
+        if verify(&message, &mac) {
+            Some(Forgery {
+                message,
+                mac,
+                key_length,
+            })
+        } else {
+            None
+        }
+    })
+}
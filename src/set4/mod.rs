@@ -0,0 +1,8 @@
+pub mod cbc_key_as_iv;
+pub mod ctr_bit_flipping;
+pub mod md4;
+pub mod merkle_damgard;
+pub mod random_access_ctr;
+pub mod sha1;
+pub mod sha256;
+pub mod sha512;
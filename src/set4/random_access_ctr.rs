@@ -0,0 +1,81 @@
+// challenge 25: CTR ciphertext that can be edited in place
+use crate::set1::aes::Aes;
+use crate::set1::block_cipher::BlockCipher;
+use crate::set2;
+use crate::set3::ctr::{CounterLayout, Keystream};
+use rand::Rng;
+
+// overwrite the plaintext under ciphertext at offset with newtext, extending the ciphertext if
+// newtext runs past its end. only the keystream covering newtext is generated
+pub fn edit(
+    ciphertext: &mut Vec<u8>,
+    key: &[u8],
+    nonce: &[u8],
+    offset: usize,
+    newtext: &[u8],
+) -> Result<(), String> {
+    if offset > ciphertext.len() {
+        return Err(format!(
+            "Offset {} is past the end of the {} byte ciphertext",
+            offset,
+            ciphertext.len()
+        ));
+    }
+    let mut keystream = Keystream::new(Aes::new(key)?, nonce, CounterLayout::LittleEndian64)?;
+    keystream.seek(offset as u64);
+    let mut encrypted = newtext.to_vec();
+    keystream.apply_keystream(&mut encrypted)?;
+
+    let end = offset + encrypted.len();
+    if end > ciphertext.len() {
+        ciphertext.resize(end, 0);
+    }
+    ciphertext[offset..end].copy_from_slice(&encrypted);
+    Ok(())
+}
+
+// encrypts under a key and nonce the caller never sees, and edits any ciphertext under them
+pub struct EditService {
+    key: [u8; 16],
+    nonce: [u8; 8],
+}
+
+impl EditService {
+    pub fn new() -> EditService {
+        EditService {
+            key: set2::random_aes_key(),
+            nonce: rand::thread_rng().gen(),
+        }
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        let mut ciphertext = Vec::new();
+        edit(&mut ciphertext, &self.key, &self.nonce, 0, plaintext).unwrap();
+        ciphertext
+    }
+
+    pub fn edit(
+        &self,
+        ciphertext: &[u8],
+        offset: usize,
+        newtext: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let mut edited = ciphertext.to_vec();
+        edit(&mut edited, &self.key, &self.nonce, offset, newtext)?;
+        Ok(edited)
+    }
+}
+
+impl Default for EditService {
+    fn default() -> EditService {
+        EditService::new()
+    }
+}
+
+// writing the ciphertext back over itself xors the keystream out of it
+pub fn recover_plaintext<F>(ciphertext: &[u8], edit: F) -> Result<Vec<u8>, String>
+where
+    F: Fn(&[u8], usize, &[u8]) -> Result<Vec<u8>, String>,
+{
+    edit(ciphertext, 0, ciphertext)
+}
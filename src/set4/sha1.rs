@@ -0,0 +1,95 @@
+// SHA-1 (FIPS 180-4), with its state out in the open so hashing can resume from any digest
+use super::merkle_damgard::{self, ByteOrder, MerkleDamgard};
+
+pub const BLOCK_SIZE: usize = 64;
+pub const DIGEST_SIZE: usize = 20;
+// h0..h4
+pub const INITIAL_STATE: [u32; 5] = [
+    0x6745_2301,
+    0xefcd_ab89,
+    0x98ba_dcfe,
+    0x1032_5476,
+    0xc3d2_e1f0,
+];
+
+// hash incrementally with merkle_damgard::Hasher<Sha1>
+#[derive(Clone, Copy, Debug)]
+pub struct Sha1;
+
+impl MerkleDamgard for Sha1 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    const LENGTH_SIZE: usize = 8;
+    const LENGTH_BYTE_ORDER: ByteOrder = ByteOrder::BigEndian;
+
+    type State = [u32; 5];
+    type Digest = [u8; DIGEST_SIZE];
+
+    const INITIAL_STATE: [u32; 5] = INITIAL_STATE;
+
+    fn compress(state: &mut [u32; 5], block: &[u8]) {
+        compress(state, arrayref::array_ref!(block, 0, BLOCK_SIZE))
+    }
+
+    fn state_to_digest(state: &[u32; 5]) -> [u8; DIGEST_SIZE] {
+        let mut digest = [0; DIGEST_SIZE];
+        for (bytes, word) in digest.chunks_mut(4).zip(state.iter()) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    fn state_from_digest(digest: &[u8; DIGEST_SIZE]) -> [u32; 5] {
+        let mut state = [0; 5];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_be_bytes(*arrayref::array_ref!(bytes, 0, 4));
+        }
+        state
+    }
+}
+
+pub fn compress(state: &mut [u32; 5], block: &[u8; BLOCK_SIZE]) {
+    let mut w = [0u32; 80];
+    for (word, bytes) in w.iter_mut().zip(block.chunks(4)) {
+        *word = u32::from_be_bytes(*arrayref::array_ref!(bytes, 0, 4));
+    }
+    for t in 16..80 {
+        w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (t, &word) in w.iter().enumerate() {
+        let (f, k) = match t {
+            0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+            20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+            _ => (b ^ c ^ d, 0xca62_c1d6),
+        };
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(word);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+    for (h, x) in state.iter_mut().zip([a, b, c, d, e].iter()) {
+        *h = h.wrapping_add(*x);
+    }
+}
+
+pub fn sha1(data: &[u8]) -> [u8; DIGEST_SIZE] {
+    Sha1::hash(data)
+}
+
+// challenge 28: a MAC that length extension breaks
+pub fn secret_prefix_mac(key: &[u8], message: &[u8]) -> [u8; DIGEST_SIZE] {
+    merkle_damgard::secret_prefix_mac::<Sha1>(key, message)
+}
+
+pub fn verify_secret_prefix_mac(key: &[u8], message: &[u8], mac: &[u8]) -> bool {
+    secret_prefix_mac(key, message)[..] == *mac
+}